@@ -1,8 +1,10 @@
-use sp_core::{Pair, Public, sr25519};
+use std::str::FromStr;
+use sp_core::{Pair, Public, ecdsa, sr25519};
 use node_template_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	SudoConfig, SystemConfig, PolkaSwapConfig, WASM_BINARY, Signature
 };
+use node_template_runtime::pallet_polkaswap::{entities::EthAddress, eth_signer::eth_address};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{Verify, IdentifyAccount};
@@ -11,6 +13,16 @@ use sc_service::ChainType;
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// Vault contract address for development networks
+const DEV_VAULT_CONTRACT_ADDRESS: &str = "e7f1725E7734CE288F8367e1Bb143E90bb3F0512";
+
+/// Ethereum block of vault contract deployment for development networks, sync starts from it
+/// The first block is safe for contracts deployed to a fresh local chain
+const DEV_VAULT_DEPLOYMENT_BLOCK: u32 = 1;
+
+/// Token contract address for development networks (DAI on our case)
+const DEV_TOKEN_CONTRACT_ADDRESS: &str = "5FbDB2315678afecb367f032d93F642f64180aa3";
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
@@ -30,6 +42,18 @@ pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
+/// Parse ethereum address from hex string.
+pub fn eth_address_from_hex(s: &str) -> EthAddress {
+	EthAddress::from_str(s.trim_start_matches("0x"))
+		.expect("static values are valid; qed")
+}
+
+/// Generate ethereum address of withdrawal signer from seed.
+pub fn eth_signer_from_seed(seed: &str) -> EthAddress {
+	eth_address(&get_from_seed::<ecdsa::Public>(seed))
+		.expect("keys derived from seed are valid; qed")
+}
+
 /// Generate an Aura authority key.
 pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(
@@ -62,6 +86,20 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 			],
+			// Relayers
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
+			// Vault contract address
+			eth_address_from_hex(DEV_VAULT_CONTRACT_ADDRESS),
+			// Vault deployment block
+			DEV_VAULT_DEPLOYMENT_BLOCK,
+			// Token contract address
+			eth_address_from_hex(DEV_TOKEN_CONTRACT_ADDRESS),
+			// Withdrawal signers
+			vec![
+				eth_signer_from_seed("Alice"),
+			],
 			true,
 		),
 		// Bootnodes
@@ -110,6 +148,20 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			// Relayers
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
+			// Vault contract address
+			eth_address_from_hex(DEV_VAULT_CONTRACT_ADDRESS),
+			// Vault deployment block
+			DEV_VAULT_DEPLOYMENT_BLOCK,
+			// Token contract address
+			eth_address_from_hex(DEV_TOKEN_CONTRACT_ADDRESS),
+			// Withdrawal signers
+			vec![
+				eth_signer_from_seed("Alice"),
+			],
 			true,
 		),
 		// Bootnodes
@@ -131,6 +183,11 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	relayers: Vec<AccountId>,
+	vault_contract_address: EthAddress,
+	vault_deployment_block: u32,
+	token_contract_address: EthAddress,
+	eth_signers: Vec<EthAddress>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -153,5 +210,22 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			key: root_key,
 		}),
+		pallet_polkaswap: Some(PolkaSwapConfig {
+			vault_contract_address,
+			// ETH/token pools with token decimals
+			tokens: vec![(token_contract_address, 6)],
+			// Whole tokens per 1 ETH in empty pools
+			initial_ratio: 1000,
+			minimal_liquidity: 1000,
+			eth_confirmation_depth: 3,
+			// Sync starts from vault deployment block, so its events are not missed
+			eth_start_block: vault_deployment_block,
+			eth_sync_batch_size: 10,
+			// Logs are kept for about 2 months of ethereum blocks
+			processed_logs_depth: 400_000,
+			relayers,
+			relayer_threshold: 1,
+			eth_signers,
+		}),
 	}
 }
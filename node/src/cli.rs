@@ -8,6 +8,12 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Ethereum JSON RPC endpoint used by offchain workers to sync polkaswap.
+	/// Could be repeated, the next endpoint is used if the previous one fails.
+	/// Endpoint of runtime is used if none is given.
+	#[structopt(long = "eth-rpc-url", value_name = "URL")]
+	pub eth_rpc_urls: Vec<String>,
 }
 
 #[derive(Debug, StructOpt)]
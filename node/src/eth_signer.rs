@@ -0,0 +1,54 @@
+//! Keystore backed signer of Ethereum messages used by polkaswap offchain worker.
+
+use std::sync::Arc;
+use sc_client_api::execution_extensions::ExtensionsFactory;
+use sc_keystore::KeyStorePtr;
+use sp_core::{crypto::{KeyTypeId, Pair}, ecdsa, offchain::{Capabilities, Capability}};
+use sp_externalities::Extensions;
+use node_template_runtime::pallet_polkaswap::eth_signer::{EthSignerExt, PrehashedSigner};
+
+/// Signs 32 bytes digests with ecdsa keys from node keystore without hashing them again
+pub struct KeystoreEthSigner(KeyStorePtr);
+
+impl KeystoreEthSigner {
+	pub fn new(keystore: KeyStorePtr) -> Self {
+		KeystoreEthSigner(keystore)
+	}
+}
+
+impl PrehashedSigner for KeystoreEthSigner {
+	fn sign_prehashed(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		digest: &[u8; 32],
+	) -> Option<ecdsa::Signature> {
+		let pair = self.0.read().key_pair_by_type::<ecdsa::Pair>(public, key_type).ok()?;
+		let secret = secp256k1::SecretKey::parse(&pair.seed()).ok()?;
+		let (signature, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(digest), &secret);
+
+		let mut raw = [0u8; 65];
+		raw[..64].copy_from_slice(&signature.serialize());
+		raw[64] = recovery_id.serialize();
+		Some(ecdsa::Signature::from_raw(raw))
+	}
+}
+
+/// Registers `EthSignerExt` for offchain worker calls
+pub struct EthSignerExtensionsFactory(Arc<KeystoreEthSigner>);
+
+impl EthSignerExtensionsFactory {
+	pub fn new(keystore: KeyStorePtr) -> Self {
+		EthSignerExtensionsFactory(Arc::new(KeystoreEthSigner::new(keystore)))
+	}
+}
+
+impl ExtensionsFactory for EthSignerExtensionsFactory {
+	fn extensions_for(&self, capabilities: Capabilities) -> Extensions {
+		let mut extensions = Extensions::new();
+		if capabilities.has(Capability::Keystore) {
+			extensions.register(EthSignerExt(self.0.clone()));
+		}
+		extensions
+	}
+}
@@ -1,3 +1,4 @@
 pub mod chain_spec;
+pub mod eth_signer;
 pub mod service;
 pub mod rpc;
@@ -0,0 +1,281 @@
+//! RPC methods of polkaswap pallet.
+//! Ethereum addresses are hex encoded and 256-bit amounts are decimal strings,
+//! so JavaScript clients don't have to decode SCALE.
+
+use std::sync::Arc;
+
+use futures::{future, StreamExt, TryStreamExt};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result, futures::{stream, Future, Sink, Stream}};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use log::warn;
+use node_template_runtime::{Event, Hash, opaque::Block as RuntimeBlock};
+use node_template_runtime::pallet_polkaswap::{
+	ContractError,
+	entities::{EthAddress, Pool, SwapDirection, SyncStatus, Uint256, UserBalances},
+	runtime_api::PolkaSwapApi as PolkaSwapRuntimeApi,
+};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Decode, hashing::twox_128, storage::StorageKey};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// Runtime API call failed
+const RUNTIME_ERROR: i64 = 1;
+/// Swap could not be quoted, e.g. there is not enough liquidity
+const QUOTE_ERROR: i64 = 2;
+
+/// Which amount of swap is exact, the other one is quoted
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteKind {
+	/// Input amount is exact, output amount is quoted
+	ExactInput,
+	/// Output amount is exact, input amount is quoted
+	ExactOutput,
+}
+
+/// Polkaswap RPC methods.
+#[rpc]
+pub trait PolkaSwapApi<BlockHash> {
+	/// ETH balance and balances in pools of all registered tokens
+	#[rpc(name = "polkaswap_getBalances")]
+	fn get_balances(&self, user: EthAddress, at: Option<BlockHash>) -> Result<UserBalances>;
+
+	/// Reserves, liquidity tokens supply and spot price of pool, null if token is not registered
+	#[rpc(name = "polkaswap_getPool")]
+	fn get_pool(&self, token: EthAddress, at: Option<BlockHash>) -> Result<Option<Pool>>;
+
+	/// Output amount for exact input swap or input amount for exact output swap
+	#[rpc(name = "polkaswap_quote")]
+	fn quote(
+		&self,
+		token: EthAddress,
+		direction: SwapDirection,
+		kind: QuoteKind,
+		amount: Uint256,
+		at: Option<BlockHash>,
+	) -> Result<Uint256>;
+
+	/// Last synced and next ethereum blocks
+	#[rpc(name = "polkaswap_syncStatus")]
+	fn sync_status(&self, at: Option<BlockHash>) -> Result<SyncStatus>;
+}
+
+/// Implementation of polkaswap RPC methods which calls polkaswap runtime API.
+pub struct PolkaSwap<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> PolkaSwap<C, B> {
+	/// Create new `PolkaSwap` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		PolkaSwap { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> PolkaSwap<C, Block> where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	fn block_id(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block> {
+		// If the block hash is not supplied assume the best block.
+		BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to call polkaswap runtime API.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn quote_error(e: ContractError) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(QUOTE_ERROR),
+		message: "Unable to quote swap.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block> PolkaSwapApi<<Block as BlockT>::Hash> for PolkaSwap<C, Block> where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C::Api: PolkaSwapRuntimeApi<Block>,
+{
+	fn get_balances(&self, user: EthAddress, at: Option<<Block as BlockT>::Hash>) -> Result<UserBalances> {
+		self.client.runtime_api().balances(&self.block_id(at), user).map_err(runtime_error)
+	}
+
+	fn get_pool(&self, token: EthAddress, at: Option<<Block as BlockT>::Hash>) -> Result<Option<Pool>> {
+		self.client.runtime_api().pool(&self.block_id(at), token).map_err(runtime_error)
+	}
+
+	fn quote(
+		&self,
+		token: EthAddress,
+		direction: SwapDirection,
+		kind: QuoteKind,
+		amount: Uint256,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Uint256> {
+		let api = self.client.runtime_api();
+		let at = self.block_id(at);
+
+		let quote = match kind {
+			QuoteKind::ExactInput => api.quote_exact_input(&at, token, direction, amount),
+			QuoteKind::ExactOutput => api.quote_exact_output(&at, token, direction, amount),
+		};
+		quote.map_err(runtime_error)?.map_err(quote_error)
+	}
+
+	fn sync_status(&self, at: Option<<Block as BlockT>::Hash>) -> Result<SyncStatus> {
+		self.client.runtime_api().sync_status(&self.block_id(at)).map_err(runtime_error)
+	}
+}
+
+/// Polkaswap pub/sub RPC methods.
+/// Values are pushed when block with polkaswap events becomes the best block and the value is changed
+#[rpc]
+pub trait PolkaSwapPubSubApi {
+	/// RPC Metadata
+	type Metadata;
+
+	/// Subscribe to reserves and spot price of token pool
+	#[pubsub(subscription = "polkaswap_pool", subscribe, name = "polkaswap_subscribePool")]
+	fn subscribe_pool(&self, metadata: Self::Metadata, subscriber: Subscriber<Option<Pool>>, token: EthAddress);
+
+	/// Unsubscribe from pool updates
+	#[pubsub(subscription = "polkaswap_pool", unsubscribe, name = "polkaswap_unsubscribePool")]
+	fn unsubscribe_pool(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+
+	/// Subscribe to balances of ethereum account
+	#[pubsub(subscription = "polkaswap_account", subscribe, name = "polkaswap_subscribeAccount")]
+	fn subscribe_account(&self, metadata: Self::Metadata, subscriber: Subscriber<UserBalances>, user: EthAddress);
+
+	/// Unsubscribe from account updates
+	#[pubsub(subscription = "polkaswap_account", unsubscribe, name = "polkaswap_unsubscribeAccount")]
+	fn unsubscribe_account(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+}
+
+/// Implementation of polkaswap subscriptions built on block import notifications.
+pub struct PolkaSwapPubSub<C, BE> {
+	client: Arc<C>,
+	manager: SubscriptionManager,
+	_marker: std::marker::PhantomData<BE>,
+}
+
+impl<C, BE> PolkaSwapPubSub<C, BE> where
+	BE: Backend<RuntimeBlock> + 'static,
+	C: ProvideRuntimeApi<RuntimeBlock> + HeaderBackend<RuntimeBlock>,
+	C: BlockchainEvents<RuntimeBlock> + StorageProvider<RuntimeBlock, BE>,
+	C: Send + Sync + 'static,
+{
+	/// Create new `PolkaSwapPubSub` with the given reference to the client and subscriptions executor.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		PolkaSwapPubSub {
+			client,
+			manager: SubscriptionManager::new(Arc::new(executor)),
+			_marker: Default::default(),
+		}
+	}
+
+	/// Sends current value right at the start and then its changes in blocks with polkaswap events
+	fn subscribe<T, F>(&self, subscriber: Subscriber<T>, query: F) where
+		T: Serialize + Clone + PartialEq + Send + 'static,
+		F: Fn(&C, &BlockId<RuntimeBlock>) -> Result<T> + Send + 'static,
+	{
+		let client = self.client.clone();
+		self.manager.add(subscriber, move |sink| {
+			let initial = query(&client, &BlockId::hash(client.info().best_hash));
+			let mut last = initial.as_ref().ok().cloned();
+
+			let stream = client.import_notification_stream()
+				.filter(|notification| future::ready(notification.is_new_best))
+				.filter_map(move |notification| {
+					let at = BlockId::hash(notification.hash);
+					if !has_polkaswap_events::<C, BE>(&*client, &at) {
+						return future::ready(None);
+					}
+
+					let value = query(&client, &at);
+					if let Ok(v) = &value {
+						if last.as_ref() == Some(v) {
+							return future::ready(None);
+						}
+						last = Some(v.clone());
+					}
+					future::ready(Some(value))
+				})
+				.map(|value| Ok::<_, ()>(value))
+				.compat();
+
+			sink.sink_map_err(|e| warn!("Error sending polkaswap notifications: {:?}", e))
+				.send_all(stream::iter_result(vec![Ok(initial)]).chain(stream))
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+}
+
+impl<C, BE> PolkaSwapPubSubApi for PolkaSwapPubSub<C, BE> where
+	BE: Backend<RuntimeBlock> + 'static,
+	C: ProvideRuntimeApi<RuntimeBlock> + HeaderBackend<RuntimeBlock>,
+	C: BlockchainEvents<RuntimeBlock> + StorageProvider<RuntimeBlock, BE>,
+	C: Send + Sync + 'static,
+	C::Api: PolkaSwapRuntimeApi<RuntimeBlock>,
+{
+	type Metadata = sc_rpc::Metadata;
+
+	fn subscribe_pool(&self, _metadata: Self::Metadata, subscriber: Subscriber<Option<Pool>>, token: EthAddress) {
+		self.subscribe(subscriber, move |client, at| {
+			client.runtime_api().pool(at, token).map_err(runtime_error)
+		});
+	}
+
+	fn unsubscribe_pool(&self, _metadata: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+		Ok(self.manager.cancel(id))
+	}
+
+	fn subscribe_account(&self, _metadata: Self::Metadata, subscriber: Subscriber<UserBalances>, user: EthAddress) {
+		self.subscribe(subscriber, move |client, at| {
+			client.runtime_api().balances(at, user).map_err(runtime_error)
+		});
+	}
+
+	fn unsubscribe_account(&self, _metadata: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+		Ok(self.manager.cancel(id))
+	}
+}
+
+/// Checks whether polkaswap pallet deposited events in the block
+fn has_polkaswap_events<C, BE>(client: &C, at: &BlockId<RuntimeBlock>) -> bool where
+	BE: Backend<RuntimeBlock>,
+	C: StorageProvider<RuntimeBlock, BE>,
+{
+	let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+	let events = match client.storage(at, &key) {
+		Ok(Some(data)) => data,
+		Ok(None) => return false,
+		Err(e) => {
+			warn!("Cant read events of block {:?}: {:?}", at, e);
+			return false;
+		}
+	};
+
+	match Vec::<frame_system::EventRecord<Event, Hash>>::decode(&mut &events.0[..]) {
+		Ok(records) => records.iter().any(|record| matches!(record.event, Event::pallet_polkaswap(_))),
+		Err(e) => {
+			warn!("Cant decode events of block {:?}: {:?}", at, e);
+			false
+		}
+	}
+}
@@ -2,21 +2,26 @@
 
 use std::sync::Arc;
 use std::time::Duration;
-use sc_client_api::{ExecutorProvider, RemoteBackend};
+use sc_client_api::{Backend, ExecutorProvider, RemoteBackend};
 use node_template_runtime::{self as runtime, opaque::Block, RuntimeApi};
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use node_template_runtime::pallet_polkaswap::LS_ETH_PROVIDER_URLS_KEY;
+use sc_service::{error::Error as ServiceError, ChainType, Configuration, TaskManager};
 use sp_inherents::InherentDataProviders;
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::{AuthorityPair as AuraPair};
 use sc_finality_grandpa::{FinalityProofProvider as GrandpaFinalityProofProvider, SharedVoterState};
+use sp_core::{Encode, offchain::{OffchainStorage, STORAGE_PREFIX}};
 
 // Our native executor instance.
 native_executor_instance!(
 	pub Executor,
 	node_template_runtime::api::dispatch,
 	node_template_runtime::native_version,
-	frame_benchmarking::benchmarking::HostFunctions,
+	(
+		frame_benchmarking::benchmarking::HostFunctions,
+		node_template_runtime::pallet_polkaswap::eth_signer::eth_signer::HostFunctions,
+	),
 );
 
 type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
@@ -43,11 +48,21 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
 	let client = Arc::new(client);
 
-	// Adding Alice for offchain
+	// Adding Alice for offchain on development networks only, these keys are well-known,
+	// so on other networks relayer and withdrawal signer keys are inserted with `author_insertKey`
+	if matches!(config.chain_spec.chain_type(), ChainType::Development | ChainType::Local) {
+		keystore.write().insert_ephemeral_from_seed_by_type::<runtime::pallet_polkaswap::crypto::Pair>(
+			"//Alice", runtime::pallet_polkaswap::KEY_TYPE
+		).expect("Creating key with account Alice should succeed.");
+
+		keystore.write().insert_ephemeral_from_seed_by_type::<runtime::pallet_polkaswap::eth_crypto::Pair>(
+			"//Alice", runtime::pallet_polkaswap::ETH_KEY_TYPE
+		).expect("Creating eth signer key for Alice should succeed.");
+	}
 
-	keystore.write().insert_ephemeral_from_seed_by_type::<runtime::pallet_polkaswap::crypto::Pair>(
-		"//Alice", runtime::pallet_polkaswap::KEY_TYPE
-	).expect("Creating key with account Alice should succeed.");
+	client.execution_extensions().set_extensions_factory(
+		Box::new(crate::eth_signer::EthSignerExtensionsFactory::new(keystore.clone()))
+	);
 
 
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
@@ -87,13 +102,20 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+/// Ethereum JSON RPC urls are passed to polkaswap offchain workers via offchain storage.
+pub fn new_full(config: Configuration, eth_rpc_urls: Vec<String>) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client, backend, mut task_manager, import_queue, keystore, select_chain, transaction_pool,
 		inherent_data_providers,
 		other: (block_import, grandpa_link),
 	} = new_partial(&config)?;
 
+	// Urls are written even if there are none, so urls of the previous run don't stick
+	if let Some(mut storage) = backend.offchain_storage() {
+		let urls: Vec<Vec<u8>> = eth_rpc_urls.into_iter().map(String::into_bytes).collect();
+		storage.set(STORAGE_PREFIX, LS_ETH_PROVIDER_URLS_KEY, &urls.encode());
+	}
+
 	let finality_proof_provider =
 		GrandpaFinalityProofProvider::new_for_service(backend.clone(), client.clone());
 
@@ -127,11 +149,12 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
+				subscription_executor,
 			};
 
 			crate::rpc::create_full(deps)
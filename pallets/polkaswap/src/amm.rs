@@ -0,0 +1,111 @@
+//! Constant product (x * y = k) pricing, the same as Uniswap v2.
+//! Fee is 0.3% and it's taken from input amount, so k never decreases after swap.
+//! All arithmetic is checked, so malformed amounts are rejected instead of panicking.
+
+use crate::ContractError;
+use crate::entities::Uint256;
+
+const FEE_NUMERATOR: u128 = 997;
+const FEE_DENOMINATOR: u128 = 1000;
+
+/// Returns maximum output amount for exact input amount
+/// ContractError if amount or reserves are empty or on overflow
+pub fn get_amount_out(amount_in: Uint256, reserve_in: Uint256, reserve_out: Uint256) -> Result<Uint256, ContractError> {
+	if amount_in.is_zero() {
+		return Err(ContractError::ZeroAmount);
+	}
+	if reserve_in.is_zero() || reserve_out.is_zero() {
+		return Err(ContractError::InsufficientLiquidity);
+	}
+
+	let amount_in_with_fee = amount_in.checked_mul(Uint256::from(FEE_NUMERATOR)).ok_or(ContractError::Overflow)?;
+	let numerator = amount_in_with_fee.checked_mul(reserve_out).ok_or(ContractError::Overflow)?;
+	let denominator = reserve_in.checked_mul(Uint256::from(FEE_DENOMINATOR))
+		.and_then(|r| r.checked_add(amount_in_with_fee))
+		.ok_or(ContractError::Overflow)?;
+	numerator.checked_div(denominator).ok_or(ContractError::DivisionByZero)
+}
+
+/// Returns minimum input amount for exact output amount
+/// ContractError if amount or reserves are empty, output exceeds reserve or on overflow
+pub fn get_amount_in(amount_out: Uint256, reserve_in: Uint256, reserve_out: Uint256) -> Result<Uint256, ContractError> {
+	if amount_out.is_zero() {
+		return Err(ContractError::ZeroAmount);
+	}
+	if reserve_in.is_zero() || amount_out >= reserve_out {
+		return Err(ContractError::InsufficientLiquidity);
+	}
+
+	let numerator = reserve_in.checked_mul(amount_out)
+		.and_then(|n| n.checked_mul(Uint256::from(FEE_DENOMINATOR)))
+		.ok_or(ContractError::Overflow)?;
+	let denominator = reserve_out.checked_sub(amount_out)
+		.and_then(|r| r.checked_mul(Uint256::from(FEE_NUMERATOR)))
+		.ok_or(ContractError::Overflow)?;
+	// Rounding up, so pool never gets less than required
+	numerator.checked_div(denominator)
+		.and_then(|amount| amount.checked_add(Uint256::from(1)))
+		.ok_or(ContractError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	fn k(reserve_a: Uint256, reserve_b: Uint256) -> Uint256 {
+		reserve_a.checked_mul(reserve_b).unwrap()
+	}
+
+	#[test]
+	fn amounts_match_uniswap() {
+		let reserve_in = Uint256::from(1_000_000u128);
+		let reserve_out = Uint256::from(2_000_000u128);
+
+		assert_eq!(get_amount_out(Uint256::from(1000u128), reserve_in, reserve_out).ok(), Some(Uint256::from(1992u128)));
+		assert_eq!(get_amount_in(Uint256::from(1992u128), reserve_in, reserve_out).ok(), Some(Uint256::from(1000u128)));
+		assert!(get_amount_in(reserve_out, reserve_in, reserve_out).is_err());
+		assert!(get_amount_out(Uint256::from(1000u128), Uint256::from(0), reserve_out).is_err());
+	}
+
+	proptest! {
+		#[test]
+		fn k_is_non_decreasing(
+			reserve_a in 1_000u128..1_000_000_000_000_000_000u128,
+			reserve_b in 1_000u128..1_000_000_000_000_000_000u128,
+			swaps in prop::collection::vec((any::<bool>(), any::<bool>(), 1u128..1_000_000_000_000_000_000u128), 1..20),
+		) {
+			let mut reserve_a = Uint256::from(reserve_a);
+			let mut reserve_b = Uint256::from(reserve_b);
+
+			for (a_to_b, exact_in, amount) in swaps {
+				let (reserve_in, reserve_out) = if a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+				let amount = Uint256::from(amount);
+
+				let swap = if exact_in {
+					get_amount_out(amount, reserve_in, reserve_out).ok().map(|out| (amount, out))
+				} else {
+					// Output is limited by half of reserve, so reserves don't overflow in long sequences
+					let amount_out = amount.min(reserve_out.checked_div(Uint256::from(2)).unwrap());
+					get_amount_in(amount_out, reserve_in, reserve_out).ok().map(|amount_in| (amount_in, amount_out))
+				};
+
+				if let Some((amount_in, amount_out)) = swap {
+					prop_assert!(amount_out < reserve_out);
+
+					let k_before = k(reserve_in, reserve_out);
+					let (new_in, new_out) = (reserve_in.checked_add(amount_in).unwrap(), reserve_out.checked_sub(amount_out).unwrap());
+					prop_assert!(k(new_in, new_out) >= k_before);
+
+					if a_to_b {
+						reserve_a = new_in;
+						reserve_b = new_out;
+					} else {
+						reserve_b = new_in;
+						reserve_a = new_out;
+					}
+				}
+			}
+		}
+	}
+}
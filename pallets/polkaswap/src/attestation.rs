@@ -0,0 +1,66 @@
+use frame_support::{debug, ensure, StorageDoubleMap, StorageValue, dispatch::DispatchError};
+use codec::Encode;
+use sp_core::H256;
+use sp_std::prelude::*;
+
+use crate::{BlockVotes, Error, Module, RawEvent, RelayerThreshold, RelayerVotes, Relayers, Trait};
+use crate::entities::BlockEvents;
+
+impl<T: Trait> Module<T> {
+	/// Records relayer vote for block events, votes are keyed by hash of encoded block events,
+	/// so only relayers which submitted identical content are counted together
+	/// Submitting the same content again is not counted, so batches could overlap
+	/// @return true if block reached threshold and could be applied
+	pub(crate) fn vote_for_block(who: &T::AccountId, be: &BlockEvents) -> Result<bool, DispatchError> {
+		let block_number = be.block_number;
+		let content_hash = Self::block_content_hash(be);
+
+		if let Some(voted_hash) = RelayerVotes::<T>::get(block_number, who) {
+			ensure!(voted_hash == content_hash, Error::<T>::AlreadyVoted);
+			return Ok(Self::reached_threshold(&BlockVotes::<T>::get(block_number, content_hash)));
+		}
+
+		// Other relayers submitted different content for the same block
+		for (voted_hash, _) in BlockVotes::<T>::iter_prefix(block_number) {
+			if voted_hash != content_hash {
+				debug::warn!("Conflicting votes for eth block {}: {:?} {:?}", block_number, voted_hash, content_hash);
+				Self::deposit_event(RawEvent::EthBlockVoteConflict(block_number, voted_hash, content_hash));
+			}
+		}
+
+		let mut votes = BlockVotes::<T>::get(block_number, content_hash);
+		votes.push(who.clone());
+		let reached_threshold = Self::reached_threshold(&votes);
+
+		BlockVotes::<T>::insert(block_number, content_hash, votes);
+		RelayerVotes::<T>::insert(block_number, who, content_hash);
+		Self::deposit_event(RawEvent::EthBlockVoted(who.clone(), block_number, content_hash));
+
+		Ok(reached_threshold)
+	}
+
+	/// Checks that relayer could vote for block, so batch is rejected before any block is applied
+	pub(crate) fn ensure_can_vote(who: &T::AccountId, be: &BlockEvents) -> Result<(), DispatchError> {
+		if let Some(voted_hash) = RelayerVotes::<T>::get(be.block_number, who) {
+			ensure!(voted_hash == Self::block_content_hash(be), Error::<T>::AlreadyVoted);
+		}
+		Ok(())
+	}
+
+	fn block_content_hash(be: &BlockEvents) -> H256 {
+		H256::from(sp_io::hashing::blake2_256(&be.encode()))
+	}
+
+	/// Block is applied, votes are not needed anymore
+	pub(crate) fn clear_block_votes(block_number: u32) {
+		BlockVotes::<T>::remove_prefix(block_number);
+		RelayerVotes::<T>::remove_prefix(block_number);
+	}
+
+	/// Votes of removed relayers are kept till block is applied, but they are not counted
+	fn reached_threshold(votes: &[T::AccountId]) -> bool {
+		let relayers = Relayers::<T>::get();
+		let active_votes = votes.iter().filter(|relayer| relayers.contains(relayer)).count();
+		active_votes as u32 >= RelayerThreshold::get()
+	}
+}
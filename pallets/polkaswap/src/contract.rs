@@ -0,0 +1,405 @@
+//! Contract methods which are emitted on Ethereum and applied to L2 state.
+//! They are used by block sync and by dry-run simulation.
+
+use codec::Encode;
+use core::cmp;
+use frame_support::{debug, StorageDoubleMap, StorageMap, StorageValue};
+
+use crate::{amm, ContractError, ContractEvent, EthBalance, InitialRatio, LiquidityBalance, MinimalLiquidity, Module,
+			PoolETHLiquidity, PoolTokenLiquidity, TokenBalance, Tokens, TotalSupply, Trait};
+use crate::entities::{ContractMethod, ContractMethod::*, EthAddress, Price, SenderAmount, SwapOrder, Uint256};
+use crate::withdrawal::queue_withdrawal;
+
+impl<T: Trait> Module<T> {
+	/// Applies contract method, it's not atomic: storage could be partially changed on error,
+	/// so callers run it in storage transaction
+	/// Swap deadlines are checked against ethereum block which contains the swap
+	pub(crate) fn apply_method(method: ContractMethod, eth_block: u32) -> Result<ContractEvent, ContractError> {
+		match method {
+			DepositToken(token, sa) => Self::with_token(token, sa, Self::deposit_token),
+			DepositETH(sa) => Self::deposit_eth(sa),
+			WithdrawETH(sa) => Self::withdraw_eth(sa),
+			WithdrawToken(token, sa) => Self::with_token(token, sa, Self::withdraw_token),
+			SwapToToken(token, sa) => Self::with_token(token, sa, Self::swap_to_token),
+			SwapToETH(token, sa) => Self::with_token(token, sa, Self::swap_to_eth),
+			AddLiquidity(token, sa) => Self::with_token(token, sa, Self::add_liquidity),
+			RemoveLiquidity(token, sa) => Self::with_token(token, sa, Self::remove_liquidity),
+			SwapExactETHForTokens(token, so) =>
+				Self::with_token(token, so, |t, o| Self::swap_exact_eth_for_tokens(t, o, eth_block)),
+			SwapETHForExactTokens(token, so) =>
+				Self::with_token(token, so, |t, o| Self::swap_eth_for_exact_tokens(t, o, eth_block)),
+			SwapExactTokensForETH(token, so) =>
+				Self::with_token(token, so, |t, o| Self::swap_exact_tokens_for_eth(t, o, eth_block)),
+			SwapTokensForExactETH(token, so) =>
+				Self::with_token(token, so, |t, o| Self::swap_tokens_for_exact_eth(t, o, eth_block)),
+		}
+	}
+
+	/// ==================== ::CONTRACT FUNCTIONS:: ==========================
+	/// Deposit token for user
+	fn deposit_token(token: EthAddress, sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+		debug::info!("deposit_token: {:?}", sa);
+		let updated_balance = if TokenBalance::contains_key(&sa.sender, &token) {
+				sa.amount.checked_add(TokenBalance::get(&sa.sender, &token)).ok_or(ContractError::Overflow)?
+			} else { sa.amount };
+
+		TokenBalance::insert(&sa.sender, &token, &updated_balance);
+		Ok(ContractEvent::DepositedToken(sa.sender.encode(), token.encode(), updated_balance.into()))
+
+	}
+
+	/// Deposit Eth for user
+	fn deposit_eth(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+		debug::info!("deposit_eth: {:?}", sa);
+		let updated_balance = if EthBalance::contains_key(&sa.sender) {
+							sa.amount.checked_add(EthBalance::get(&sa.sender)).ok_or(ContractError::Overflow)?
+						} else { sa.amount};
+
+		EthBalance::insert(&sa.sender, &updated_balance);
+		Ok(ContractEvent::DepositedETH(sa.sender.encode(), updated_balance.into()))
+	}
+
+	/// Withdraw function
+	/// @return SenderAmount with real numbers to be withdrawn, else ContractError
+	fn withdraw_token(token: EthAddress, sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+		if !TokenBalance::contains_key(&sa.sender, &token) {
+			return Err(ContractError::UnknownUser);
+		}
+		let amount = Self::get_min_user_token_balance(&token, &sa);
+		if amount.clone() > Uint256::from(0)  {
+			let updated_balance = TokenBalance::get(&sa.sender, &token).checked_sub(amount).ok_or(ContractError::Underflow)?;
+			TokenBalance::insert(&sa.sender, &token, &updated_balance);
+			queue_withdrawal(sa.sender, token, amount);
+			Ok(ContractEvent::WithdrawToken(sa.sender.encode(), token.encode(), updated_balance.into()))
+		} else {
+			Err(ContractError::NothingToWithdraw)
+		}
+	}
+
+	/// Withdraw function
+	/// @return SenderAmount with real numbers to be withdrawn, else ContractError
+	fn withdraw_eth(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+		if !EthBalance::contains_key(&sa.sender) {
+			return Err(ContractError::UnknownUser);
+		}
+		let amount = Self::get_min_user_eth_balance(&sa);
+		if amount.clone() > Uint256::from(0)  {
+			let updated_balance = EthBalance::get(&sa.sender).checked_sub(amount).ok_or(ContractError::Underflow)?;
+			EthBalance::insert(&sa.sender, &updated_balance);
+			// ETH is represented by zero address in withdrawals
+			queue_withdrawal(sa.sender, EthAddress::default(), amount);
+			Ok(ContractEvent::WithdrawETH(sa.sender.encode(), updated_balance.into()))
+		} else {
+			Err(ContractError::NothingToWithdraw)
+		}
+	}
+
+	/// SwapToToken
+	/// Amount means desired token amount
+	fn swap_to_token(token: EthAddress, sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+		debug::info!("swap_to_token");
+		let desired_token_amount = sa.amount;
+		let pool_token_liquidity = PoolTokenLiquidity::get(&token);
+		let pool_eth_liquidity = PoolETHLiquidity::get(&token);
+
+		if desired_token_amount >= pool_token_liquidity {
+			return Err(ContractError::InsufficientLiquidity);
+		}
+
+		// ETH amount is priced by constant product formula, fee is taken from it
+		let amount_eth_to_withdraw = amm::get_amount_in(desired_token_amount, pool_eth_liquidity, pool_token_liquidity)?;
+
+		Self::buy_tokens(token, sa.sender, amount_eth_to_withdraw, desired_token_amount)
+	}
+
+	/// SwapToETH
+	/// Amount means desired eth amount
+	fn swap_to_eth(token: EthAddress, sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+		debug::info!("swap_to_eth");
+		let desired_eth_amount = sa.amount;
+		let pool_token_liquidity = PoolTokenLiquidity::get(&token);
+		let pool_eth_liquidity = PoolETHLiquidity::get(&token);
+
+		if desired_eth_amount >= pool_eth_liquidity {
+			return Err(ContractError::InsufficientLiquidity);
+		}
+
+		// Token amount is priced by constant product formula, fee is taken from it
+		let amount_token_to_withdraw = amm::get_amount_in(desired_eth_amount, pool_token_liquidity, pool_eth_liquidity)?;
+
+		Self::sell_tokens(token, sa.sender, amount_token_to_withdraw, desired_eth_amount)
+	}
+
+	/// Swaps exact ETH amount for at least order.limit tokens
+	fn swap_exact_eth_for_tokens(token: EthAddress, order: SwapOrder, eth_block: u32) -> Result<ContractEvent, ContractError>{
+		debug::info!("swap_exact_eth_for_tokens");
+		Self::check_deadline(&order, eth_block)?;
+
+		let amount_token_out = amm::get_amount_out(order.amount, PoolETHLiquidity::get(&token), PoolTokenLiquidity::get(&token))?;
+		if amount_token_out < order.limit {
+			return Err(ContractError::InsufficientOutputAmount);
+		}
+
+		Self::buy_tokens(token, order.sender, order.amount, amount_token_out)
+	}
+
+	/// Swaps at most order.limit ETH for exact tokens amount
+	fn swap_eth_for_exact_tokens(token: EthAddress, order: SwapOrder, eth_block: u32) -> Result<ContractEvent, ContractError>{
+		debug::info!("swap_eth_for_exact_tokens");
+		Self::check_deadline(&order, eth_block)?;
+
+		let amount_eth_in = amm::get_amount_in(order.amount, PoolETHLiquidity::get(&token), PoolTokenLiquidity::get(&token))?;
+		if amount_eth_in > order.limit {
+			return Err(ContractError::ExcessiveInputAmount);
+		}
+
+		Self::buy_tokens(token, order.sender, amount_eth_in, order.amount)
+	}
+
+	/// Swaps exact tokens amount for at least order.limit ETH
+	fn swap_exact_tokens_for_eth(token: EthAddress, order: SwapOrder, eth_block: u32) -> Result<ContractEvent, ContractError>{
+		debug::info!("swap_exact_tokens_for_eth");
+		Self::check_deadline(&order, eth_block)?;
+
+		let amount_eth_out = amm::get_amount_out(order.amount, PoolTokenLiquidity::get(&token), PoolETHLiquidity::get(&token))?;
+		if amount_eth_out < order.limit {
+			return Err(ContractError::InsufficientOutputAmount);
+		}
+
+		Self::sell_tokens(token, order.sender, order.amount, amount_eth_out)
+	}
+
+	/// Swaps at most order.limit tokens for exact ETH amount
+	fn swap_tokens_for_exact_eth(token: EthAddress, order: SwapOrder, eth_block: u32) -> Result<ContractEvent, ContractError>{
+		debug::info!("swap_tokens_for_exact_eth");
+		Self::check_deadline(&order, eth_block)?;
+
+		let amount_token_in = amm::get_amount_in(order.amount, PoolTokenLiquidity::get(&token), PoolETHLiquidity::get(&token))?;
+		if amount_token_in > order.limit {
+			return Err(ContractError::ExcessiveInputAmount);
+		}
+
+		Self::sell_tokens(token, order.sender, amount_token_in, order.amount)
+	}
+
+	/// Swap should be included in ethereum block before its deadline
+	fn check_deadline(order: &SwapOrder, eth_block: u32) -> Result<(), ContractError> {
+		if Uint256::from(eth_block as u128) > order.deadline {
+			return Err(ContractError::DeadlineExpired);
+		}
+		Ok(())
+	}
+
+	/// Moves ETH from user account to the pool and tokens from the pool to user account
+	fn buy_tokens(token: EthAddress, sender: EthAddress, amount_eth_in: Uint256, amount_token_out: Uint256) -> Result<ContractEvent, ContractError>{
+		let pool_token_liquidity = PoolTokenLiquidity::get(&token);
+		let pool_eth_liquidity = PoolETHLiquidity::get(&token);
+		let amount_eth_user = EthBalance::get(&sender);
+
+		if amount_eth_in > amount_eth_user {
+			return Err(ContractError::InsufficientEthBalance);
+		}
+
+		let amount_token_user = TokenBalance::get(&sender, &token);
+
+		let updated_user_eth_balance = amount_eth_user.checked_sub(amount_eth_in).ok_or(ContractError::Underflow)?;
+		let updated_user_token_balance = amount_token_user.checked_add(amount_token_out).ok_or(ContractError::Overflow)?;
+		let updated_pool_token_liquidity = pool_token_liquidity.checked_sub(amount_token_out).ok_or(ContractError::Underflow)?;
+		let updated_pool_eth_liquidity = pool_eth_liquidity.checked_add(amount_eth_in).ok_or(ContractError::Overflow)?;
+
+		PoolTokenLiquidity::insert(&token, updated_pool_token_liquidity);
+		PoolETHLiquidity::insert(&token, updated_pool_eth_liquidity);
+
+		TokenBalance::insert(&sender, &token, &updated_user_token_balance);
+		EthBalance::insert(&sender, &updated_user_eth_balance);
+
+		Ok(ContractEvent::SwapToToken(sender.encode(), token.encode(), updated_user_token_balance.into()))
+	}
+
+	/// Moves tokens from user account to the pool and ETH from the pool to user account
+	fn sell_tokens(token: EthAddress, sender: EthAddress, amount_token_in: Uint256, amount_eth_out: Uint256) -> Result<ContractEvent, ContractError>{
+		let pool_token_liquidity = PoolTokenLiquidity::get(&token);
+		let pool_eth_liquidity = PoolETHLiquidity::get(&token);
+		let amount_token_user = TokenBalance::get(&sender, &token);
+
+		if amount_token_in > amount_token_user {
+			return Err(ContractError::InsufficientTokenBalance);
+		}
+
+		let amount_eth_user = EthBalance::get(&sender);
+
+		let updated_user_eth_balance = amount_eth_user.checked_add(amount_eth_out).ok_or(ContractError::Overflow)?;
+		let updated_user_token_balance = amount_token_user.checked_sub(amount_token_in).ok_or(ContractError::Underflow)?;
+		let updated_pool_token_liquidity = pool_token_liquidity.checked_add(amount_token_in).ok_or(ContractError::Overflow)?;
+		let updated_pool_eth_liquidity = pool_eth_liquidity.checked_sub(amount_eth_out).ok_or(ContractError::Underflow)?;
+
+		PoolTokenLiquidity::insert(&token, updated_pool_token_liquidity);
+		PoolETHLiquidity::insert(&token, updated_pool_eth_liquidity);
+
+		TokenBalance::insert(&sender, &token, &updated_user_token_balance);
+		EthBalance::insert(&sender, &updated_user_eth_balance);
+
+		Ok(ContractEvent::SwapToETH(sender.encode(), token.encode(), updated_user_eth_balance.into()))
+	}
+
+	/// AddLiquidity function
+	/// Amount means value in eth
+	/// @return SenderAmount with real numbers to be withdrawn, else ContractError
+	fn add_liquidity(token: EthAddress, sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+
+		debug::info!("add_liquidity");
+		debug::info!("Got eth amount: {:}", &sa.amount);
+
+		let total_supply = TotalSupply::get(&token);
+		debug::info!("total_supply: {:}", &total_supply);
+
+		let mut amount_eth = Self::get_min_user_eth_balance(&sa);
+		debug::info!("amount eth: {:}", &amount_eth);
+
+		let price = Self::get_price(&token)?;
+		let amount_token = price.token_amount(amount_eth).ok_or(ContractError::Overflow)?;
+		debug::info!("amount token: {:}", &amount_token);
+
+		let user_token_balance = TokenBalance::get(&sa.sender, &token);
+		let amount_token_upd = cmp::min(amount_token, user_token_balance);
+
+		if amount_token_upd < amount_token {
+			amount_eth = price.eth_amount(amount_token_upd).ok_or(ContractError::DivisionByZero)?;
+			debug::info!("amount_eth: {:?}", amount_eth);
+			debug::info!("amount_token_upd: {:?}", amount_token);
+		}
+		let amount_token = amount_token_upd;
+
+		debug::info!("amount_eth: {:?}", amount_eth);
+		debug::info!("amount_token_upd: {:?}", amount_token);
+
+		let liquidity : Uint256;
+		let pool_token_liquidity = PoolTokenLiquidity::get(&token);
+		let pool_eth_liquidity = PoolETHLiquidity::get(&token);
+
+		if total_supply.is_zero() {
+			let initial_value = amount_eth.integer_sqrt().checked_mul(amount_token.integer_sqrt())
+				.ok_or(ContractError::Overflow)?;
+
+			// Minimal liquidity is locked forever
+			liquidity = initial_value.checked_sub(Uint256::from(MinimalLiquidity::get()))
+				.ok_or(ContractError::InsufficientLiquidity)?;
+
+		} else {
+			let liquidity_by_eth = amount_eth.checked_mul(total_supply)
+				.ok_or(ContractError::Overflow)?
+				.checked_div(pool_eth_liquidity)
+				.ok_or(ContractError::DivisionByZero)?;
+			let liquidity_by_token = amount_token.checked_mul(total_supply)
+				.ok_or(ContractError::Overflow)?
+				.checked_div(pool_token_liquidity)
+				.ok_or(ContractError::DivisionByZero)?;
+			liquidity = cmp::min(liquidity_by_eth, liquidity_by_token);
+		}
+
+		let user_liquidity_balance = LiquidityBalance::get(&sa.sender, &token)
+			.checked_add(liquidity).ok_or(ContractError::Overflow)?;
+
+		let updated_token_balance = user_token_balance.checked_sub(amount_token).ok_or(ContractError::Underflow)?;
+		let updated_eth_balance = EthBalance::get(&sa.sender).checked_sub(amount_eth).ok_or(ContractError::Underflow)?;
+		let updated_pool_eth_liquidity = pool_eth_liquidity.checked_add(amount_eth).ok_or(ContractError::Overflow)?;
+		let updated_pool_token_liquidity = pool_token_liquidity.checked_add(amount_token).ok_or(ContractError::Overflow)?;
+		let updated_total_supply = total_supply.checked_add(liquidity).ok_or(ContractError::Overflow)?;
+
+		TokenBalance::insert(&sa.sender, &token, &updated_token_balance);
+		EthBalance::insert(&sa.sender, updated_eth_balance);
+
+		// Updating pool liquidity parameters
+		LiquidityBalance::insert(&sa.sender, &token, &user_liquidity_balance);
+		PoolETHLiquidity::insert(&token, updated_pool_eth_liquidity);
+		PoolTokenLiquidity::insert(&token, updated_pool_token_liquidity);
+		TotalSupply::insert(&token, updated_total_supply);
+		Ok(ContractEvent::AddLiquidity(sa.sender.encode(), token.encode(), user_liquidity_balance.into()))
+
+	}
+
+	/// RemoveLiquidity function
+	/// Amount means value in liquidity tokens
+	/// @return SenderAmount with real numbers to be withdrawn, else ContractError
+	fn remove_liquidity(token: EthAddress, sa: SenderAmount) -> Result<ContractEvent, ContractError>{
+		let amount_to_remove =  Self::get_min_user_liquidity_balance(&token, &sa);
+		let total_supply = TotalSupply::get(&token);
+
+		if total_supply.is_zero() {
+			return  Err(ContractError::InsufficientLiquidity);
+		}
+
+		let pool_eth_liquidity = PoolETHLiquidity::get(&token);
+		let pool_token_liquidity = PoolTokenLiquidity::get(&token);
+
+
+		let amount_eth_to_return = pool_eth_liquidity.checked_mul(amount_to_remove)
+			.ok_or(ContractError::Overflow)?
+			.checked_div(total_supply)
+			.ok_or(ContractError::DivisionByZero)?;
+		let amount_token_to_return = pool_token_liquidity.checked_mul(amount_to_remove)
+			.ok_or(ContractError::Overflow)?
+			.checked_div(total_supply)
+			.ok_or(ContractError::DivisionByZero)?;
+
+		let updated_total_supply = total_supply.checked_sub(amount_to_remove).ok_or(ContractError::Underflow)?;
+
+		let updated_user_liquidity_balance = LiquidityBalance::get(&sa.sender, &token)
+			.checked_sub(amount_to_remove).ok_or(ContractError::Underflow)?;
+
+		let user_eth_balance = EthBalance::get(&sa.sender)
+			.checked_add(amount_eth_to_return).ok_or(ContractError::Overflow)?;
+		let user_token_balance = TokenBalance::get(&sa.sender, &token)
+			.checked_add(amount_token_to_return).ok_or(ContractError::Overflow)?;
+		let updated_pool_eth_liquidity = pool_eth_liquidity
+			.checked_sub(amount_eth_to_return).ok_or(ContractError::Underflow)?;
+		let updated_pool_token_liquidity = pool_token_liquidity
+			.checked_sub(amount_token_to_return).ok_or(ContractError::Underflow)?;
+
+		LiquidityBalance::insert(&sa.sender, &token, updated_user_liquidity_balance);
+		PoolETHLiquidity::insert(&token, updated_pool_eth_liquidity);
+		PoolTokenLiquidity::insert(&token, updated_pool_token_liquidity);
+		TotalSupply::insert(&token, updated_total_supply);
+
+		EthBalance::insert(&sa.sender, &user_eth_balance);
+		TokenBalance::insert(&sa.sender, &token, &user_token_balance);
+		Ok(ContractEvent::RemoveLiquidity(sa.sender.encode(), token.encode(), amount_to_remove.into()))
+	}
+
+	/// ==================== ::CONTRACT HELPERS:: ==========================
+	fn get_min_user_token_balance(token: &EthAddress, sa: &SenderAmount) -> Uint256 {
+		let user_token_balance = TokenBalance::get(&sa.sender, token);
+		cmp::min(sa.amount, user_token_balance)
+	}
+
+	fn get_min_user_eth_balance(sa: &SenderAmount) -> Uint256 {
+		let user_eth_balance = EthBalance::get(&sa.sender);
+		cmp::min(sa.amount, user_eth_balance)
+	}
+
+	fn get_min_user_liquidity_balance(token: &EthAddress, sa: &SenderAmount) -> Uint256 {
+		let user_liquidity_token_balance = LiquidityBalance::get(&sa.sender, token);
+		cmp::min(sa.amount, user_liquidity_token_balance)
+	}
+
+	/// Spot price of the pool, initial ratio is used for empty pool
+	fn get_price(token: &EthAddress) -> Result<Price, ContractError> {
+		let token_liquidity = PoolTokenLiquidity::get(token);
+		let eth_liquidity = PoolETHLiquidity::get(token);
+		if eth_liquidity.is_zero() {
+			let token_decimals = Tokens::get(token).ok_or(ContractError::TokenNotRegistered)?;
+			return Price::from_ratio(InitialRatio::get(), token_decimals).ok_or(ContractError::Overflow);
+		}
+
+		Price::from_reserves(token_liquidity, eth_liquidity).ok_or(ContractError::Overflow)
+	}
+
+	/// Runs method against pool of registered token only
+	fn with_token<P, F>(token: EthAddress, payload: P, method: F) -> Result<ContractEvent, ContractError>
+		where F: FnOnce(EthAddress, P) -> Result<ContractEvent, ContractError> {
+		if !Tokens::contains_key(&token) {
+			return Err(ContractError::TokenNotRegistered);
+		}
+		method(token, payload)
+	}
+}
@@ -1,20 +1,24 @@
 use sp_std::prelude::*;
 use sp_std::fmt::{Debug, Formatter};
 use codec::{Encode, Decode};
-use crate::entities::ContractMethod;
+use crate::entities::{ContractMethod, Hash256, LogId};
 use sp_std::fmt;
 
 #[derive(Eq, Encode, Decode, PartialEq, Clone)]
 pub struct BlockEvents {
 	pub(crate) block_number: u32,
-	pub(crate) methods: Vec<ContractMethod>
+	pub(crate) block_hash: Hash256,
+	pub(crate) parent_hash: Hash256,
+	/// Methods in order of logs in block, paired with identifier of log which emitted them
+	pub(crate) methods: Vec<(LogId, ContractMethod)>
 }
 
 impl Debug for BlockEvents {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		write!(f, "[ BLOCK FROM ETH TO SYNC ]\nBlock number: {}", &self.block_number)?;
-		for cmd in self.methods.clone() {
-			write!(f, "{}", cmd)?;
+		write!(f, "\nBlock hash: {}\nParent hash: {}\n", &self.block_hash, &self.parent_hash)?;
+		for (log_id, cmd) in self.methods.clone() {
+			write!(f, "{} (log: {})\n", cmd, log_id)?;
 		}
 		write!(f, "--------")
 	}
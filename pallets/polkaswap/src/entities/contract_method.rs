@@ -6,31 +6,43 @@ use sp_std::{
 	prelude::*,
 };
 
+use super::eth_address::EthAddress;
 use super::sender_amount::SenderAmount;
+use super::swap_order::SwapOrder;
 
+/// Methods which are emitted by vault & token contracts on Ethereum
+/// Methods which manage tokens carry token contract address of the pool
 #[derive(Eq, PartialEq, Copy, Clone)]
 pub enum ContractMethod {
-	DepositToken(SenderAmount),
+	DepositToken(EthAddress, SenderAmount),
 	DepositETH(SenderAmount),
 	WithdrawETH(SenderAmount),
-	WithdrawToken(SenderAmount),
-	SwapToToken(SenderAmount),
-	SwapToETH(SenderAmount),
-	AddLiquidity(SenderAmount),
-	RemoveLiquidity(SenderAmount),
+	WithdrawToken(EthAddress, SenderAmount),
+	SwapToToken(EthAddress, SenderAmount),
+	SwapToETH(EthAddress, SenderAmount),
+	AddLiquidity(EthAddress, SenderAmount),
+	RemoveLiquidity(EthAddress, SenderAmount),
+	SwapExactETHForTokens(EthAddress, SwapOrder),
+	SwapETHForExactTokens(EthAddress, SwapOrder),
+	SwapExactTokensForETH(EthAddress, SwapOrder),
+	SwapTokensForExactETH(EthAddress, SwapOrder),
 }
 
 impl Encode for ContractMethod {
 	fn encode(&self) -> Vec<u8> {
 		match self {
-			ContractMethod::DepositToken(sa) => { ContractMethod::encode_item(0u8, sa) }
+			ContractMethod::DepositToken(t, sa) => { ContractMethod::encode_token_item(0u8, t, sa) }
 			ContractMethod::DepositETH(sa) => { ContractMethod::encode_item(1u8, sa) }
 			ContractMethod::WithdrawETH(sa) => { ContractMethod::encode_item(2u8, sa) }
-			ContractMethod::WithdrawToken(sa) => { ContractMethod::encode_item(3u8, sa) }
-			ContractMethod::SwapToToken(sa) => { ContractMethod::encode_item(4u8, sa) }
-			ContractMethod::SwapToETH(sa) => { ContractMethod::encode_item(5u8, sa) }
-			ContractMethod::AddLiquidity(sa) => { ContractMethod::encode_item(6u8, sa) }
-			ContractMethod::RemoveLiquidity(sa) => { ContractMethod::encode_item(7u8, sa) }
+			ContractMethod::WithdrawToken(t, sa) => { ContractMethod::encode_token_item(3u8, t, sa) }
+			ContractMethod::SwapToToken(t, sa) => { ContractMethod::encode_token_item(4u8, t, sa) }
+			ContractMethod::SwapToETH(t, sa) => { ContractMethod::encode_token_item(5u8, t, sa) }
+			ContractMethod::AddLiquidity(t, sa) => { ContractMethod::encode_token_item(6u8, t, sa) }
+			ContractMethod::RemoveLiquidity(t, sa) => { ContractMethod::encode_token_item(7u8, t, sa) }
+			ContractMethod::SwapExactETHForTokens(t, so) => { ContractMethod::encode_swap_item(8u8, t, so) }
+			ContractMethod::SwapETHForExactTokens(t, so) => { ContractMethod::encode_swap_item(9u8, t, so) }
+			ContractMethod::SwapExactTokensForETH(t, so) => { ContractMethod::encode_swap_item(10u8, t, so) }
+			ContractMethod::SwapTokensForExactETH(t, so) => { ContractMethod::encode_swap_item(11u8, t, so) }
 		}
 	}
 }
@@ -38,28 +50,107 @@ impl Encode for ContractMethod {
 impl Decode for ContractMethod {
 	fn decode<I: Input>(value: &mut I) -> Result<Self, Error> {
 		let cm_type = value.read_byte()?;
-		let sa = SenderAmount::decode(value)?;
 		match cm_type {
-			0u8 => Ok(ContractMethod::DepositToken(sa)),
-			1u8 => Ok(ContractMethod::DepositETH(sa)),
-			2u8 => Ok(ContractMethod::WithdrawETH(sa)),
-			3u8 => Ok(ContractMethod::WithdrawToken(sa)),
-			4u8 => Ok(ContractMethod::SwapToToken(sa)),
-			5u8 => Ok(ContractMethod::SwapToETH(sa)),
-			6u8 => Ok(ContractMethod::AddLiquidity(sa)),
-			7u8 => Ok(ContractMethod::RemoveLiquidity(sa)),
+			1u8 => Ok(ContractMethod::DepositETH(SenderAmount::decode(value)?)),
+			2u8 => Ok(ContractMethod::WithdrawETH(SenderAmount::decode(value)?)),
+			0u8 | 3u8..=7u8 => {
+				let token = EthAddress::decode(value)?;
+				let sa = SenderAmount::decode(value)?;
+				match cm_type {
+					0u8 => Ok(ContractMethod::DepositToken(token, sa)),
+					3u8 => Ok(ContractMethod::WithdrawToken(token, sa)),
+					4u8 => Ok(ContractMethod::SwapToToken(token, sa)),
+					5u8 => Ok(ContractMethod::SwapToETH(token, sa)),
+					6u8 => Ok(ContractMethod::AddLiquidity(token, sa)),
+					_ => Ok(ContractMethod::RemoveLiquidity(token, sa)),
+				}
+			}
+			8u8..=11u8 => {
+				let token = EthAddress::decode(value)?;
+				let so = SwapOrder::decode(value)?;
+				match cm_type {
+					8u8 => Ok(ContractMethod::SwapExactETHForTokens(token, so)),
+					9u8 => Ok(ContractMethod::SwapETHForExactTokens(token, so)),
+					10u8 => Ok(ContractMethod::SwapExactTokensForETH(token, so)),
+					_ => Ok(ContractMethod::SwapTokensForExactETH(token, so)),
+				}
+			}
 			_ => { Err(Error::from("Unknown contract method type"))}
 		}
 	}
 }
 
+/// Kind of contract method without its payload, it's reported in events
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Copy, Clone)]
+pub enum ContractMethodKind {
+	DepositToken,
+	DepositETH,
+	WithdrawETH,
+	WithdrawToken,
+	SwapToToken,
+	SwapToETH,
+	AddLiquidity,
+	RemoveLiquidity,
+	SwapExactETHForTokens,
+	SwapETHForExactTokens,
+	SwapExactTokensForETH,
+	SwapTokensForExactETH,
+}
+
 impl ContractMethod {
+	pub fn kind(&self) -> ContractMethodKind {
+		match self {
+			ContractMethod::DepositToken(..) => ContractMethodKind::DepositToken,
+			ContractMethod::DepositETH(_) => ContractMethodKind::DepositETH,
+			ContractMethod::WithdrawETH(_) => ContractMethodKind::WithdrawETH,
+			ContractMethod::WithdrawToken(..) => ContractMethodKind::WithdrawToken,
+			ContractMethod::SwapToToken(..) => ContractMethodKind::SwapToToken,
+			ContractMethod::SwapToETH(..) => ContractMethodKind::SwapToETH,
+			ContractMethod::AddLiquidity(..) => ContractMethodKind::AddLiquidity,
+			ContractMethod::RemoveLiquidity(..) => ContractMethodKind::RemoveLiquidity,
+			ContractMethod::SwapExactETHForTokens(..) => ContractMethodKind::SwapExactETHForTokens,
+			ContractMethod::SwapETHForExactTokens(..) => ContractMethodKind::SwapETHForExactTokens,
+			ContractMethod::SwapExactTokensForETH(..) => ContractMethodKind::SwapExactTokensForETH,
+			ContractMethod::SwapTokensForExactETH(..) => ContractMethodKind::SwapTokensForExactETH,
+		}
+	}
+
+	/// Ethereum account which called the method
+	pub fn sender(&self) -> EthAddress {
+		match self {
+			ContractMethod::DepositETH(sa) | ContractMethod::WithdrawETH(sa) => sa.sender,
+			ContractMethod::DepositToken(_, sa) | ContractMethod::WithdrawToken(_, sa) |
+			ContractMethod::SwapToToken(_, sa) | ContractMethod::SwapToETH(_, sa) |
+			ContractMethod::AddLiquidity(_, sa) | ContractMethod::RemoveLiquidity(_, sa) => sa.sender,
+			ContractMethod::SwapExactETHForTokens(_, so) | ContractMethod::SwapETHForExactTokens(_, so) |
+			ContractMethod::SwapExactTokensForETH(_, so) | ContractMethod::SwapTokensForExactETH(_, so) => so.sender,
+		}
+	}
+
 	fn encode_item(cm_type: u8, sa: &SenderAmount) -> Vec<u8> {
 		let mut cm_bytes: Vec<u8> = Vec::from([cm_type]);
 		let mut sa_bytes = sa.encode();
 		cm_bytes.append(&mut sa_bytes);
 		cm_bytes
 	}
+
+	fn encode_token_item(cm_type: u8, token: &EthAddress, sa: &SenderAmount) -> Vec<u8> {
+		let mut cm_bytes: Vec<u8> = Vec::from([cm_type]);
+		let mut token_bytes = token.encode();
+		let mut sa_bytes = sa.encode();
+		cm_bytes.append(&mut token_bytes);
+		cm_bytes.append(&mut sa_bytes);
+		cm_bytes
+	}
+
+	fn encode_swap_item(cm_type: u8, token: &EthAddress, so: &SwapOrder) -> Vec<u8> {
+		let mut cm_bytes: Vec<u8> = Vec::from([cm_type]);
+		let mut token_bytes = token.encode();
+		let mut so_bytes = so.encode();
+		cm_bytes.append(&mut token_bytes);
+		cm_bytes.append(&mut so_bytes);
+		cm_bytes
+	}
 }
 
 
@@ -73,8 +164,8 @@ impl Debug for ContractMethod {
 impl Display for ContractMethod {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		match self {
-			ContractMethod::DepositToken(dm) => {
-				write!(f, "[Deposit Token]: from: {}, amount: {}", dm.sender, dm.amount)
+			ContractMethod::DepositToken(t, dm) => {
+				write!(f, "[Deposit Token]: token: {}, from: {}, amount: {}", t, dm.sender, dm.amount)
 			}
 
 			ContractMethod::DepositETH(dm) => {
@@ -85,26 +176,45 @@ impl Display for ContractMethod {
 				write!(f, "[Withdraw ETH]: from: {}, amount: {}", dm.sender, dm.amount)
 			}
 
-			ContractMethod::WithdrawToken(dm) => {
-				write!(f, "[Withdraw Token]: from: {}, amount: {}", dm.sender, dm.amount)
+			ContractMethod::WithdrawToken(t, dm) => {
+				write!(f, "[Withdraw Token]: token: {}, from: {}, amount: {}", t, dm.sender, dm.amount)
+			}
+
+			ContractMethod::SwapToToken(t, dm) => {
+				write!(f, "[Swap to Token]: token: {}, from: {}, amount: {}", t, dm.sender, dm.amount)
 			}
 
-			ContractMethod::SwapToToken(dm) => {
-				write!(f, "[Swap to Token]: from: {}, amount: {}", dm.sender, dm.amount)
+			ContractMethod::SwapToETH(t, dm) => {
+				write!(f, "[Swap to ETH]: token: {}, from: {}, amount: {}", t, dm.sender, dm.amount)
 			}
 
-			ContractMethod::SwapToETH(dm) => {
-				write!(f, "[Swap to ETH]: from: {}, amount: {}", dm.sender, dm.amount)
+			ContractMethod::AddLiquidity(t, dm) => {
+				write!(f, "[Add liquidity]: token: {}, from: {}, amount: {}", t, dm.sender, dm.amount)
 			}
 
-			ContractMethod::AddLiquidity(dm) => {
-				write!(f, "[Add liquidity]: from: {}, amount: {}", dm.sender, dm.amount)
+			ContractMethod::RemoveLiquidity(t, dm) => {
+				write!(f, "[Remove liquidity]: token: {}, from: {}, amount: {}", t, dm.sender, dm.amount)
 			}
 
-			ContractMethod::RemoveLiquidity(dm) => {
-				write!(f, "[Remove liquidity]: from: {}, amount: {}", dm.sender, dm.amount)
+			ContractMethod::SwapExactETHForTokens(t, so) => {
+				write!(f, "[Swap exact ETH for tokens]: token: {}, from: {}, amount in: {}, min amount out: {}, deadline: {}",
+					   t, so.sender, so.amount, so.limit, so.deadline)
+			}
+
+			ContractMethod::SwapETHForExactTokens(t, so) => {
+				write!(f, "[Swap ETH for exact tokens]: token: {}, from: {}, amount out: {}, max amount in: {}, deadline: {}",
+					   t, so.sender, so.amount, so.limit, so.deadline)
+			}
+
+			ContractMethod::SwapExactTokensForETH(t, so) => {
+				write!(f, "[Swap exact tokens for ETH]: token: {}, from: {}, amount in: {}, min amount out: {}, deadline: {}",
+					   t, so.sender, so.amount, so.limit, so.deadline)
+			}
+
+			ContractMethod::SwapTokensForExactETH(t, so) => {
+				write!(f, "[Swap tokens for exact ETH]: token: {}, from: {}, amount out: {}, max amount in: {}, deadline: {}",
+					   t, so.sender, so.amount, so.limit, so.deadline)
 			}
 		}
 	}
 }
-
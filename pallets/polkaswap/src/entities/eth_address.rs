@@ -4,11 +4,13 @@ use frame_support::debug;
 use sp_std::{fmt, prelude::*};
 use sp_std::fmt::{Display, Formatter};
 use sp_std::str::FromStr;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// EthAddress struct
 /// a wrapper for Address stuct with Encode, Decode traits
 /// implemented for Parity codec
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
 pub struct EthAddress(Address);
 
 impl Encode for EthAddress {
@@ -50,6 +52,12 @@ impl From<Address> for EthAddress {
 	}
 }
 
+impl From<EthAddress> for Address {
+	fn from(value: EthAddress) -> Self {
+		value.0
+	}
+}
+
 impl FromStr for EthAddress {
 	type Err = Error;
 
@@ -60,3 +68,21 @@ impl FromStr for EthAddress {
 		}
 	}
 }
+
+/// Serialized as 0x-prefixed hex string, so addresses could be set in JSON chain specs
+#[cfg(feature = "std")]
+impl Serialize for EthAddress {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+		let hex_value = hex::encode(self.0.as_bytes());
+		serializer.serialize_str(["0x", hex_value.as_str()].concat().as_str())
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for EthAddress {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+		let s = String::deserialize(deserializer)?;
+		EthAddress::from_str(s.trim_start_matches("0x"))
+			.map_err(|_| serde::de::Error::custom("Cant convert str to EthAddress"))
+	}
+}
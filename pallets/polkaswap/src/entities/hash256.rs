@@ -8,7 +8,7 @@ use sp_std::{fmt, prelude::*};
 /// Hash256 struct
 /// a wrapper for Hash stuct with Encode, Decode traits
 /// implemented for Parity codec
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
 pub struct Hash256(Hash);
 
 impl Encode for Hash256 {
@@ -25,13 +25,13 @@ impl EncodeLike for Hash256 {}
 
 impl Decode for Hash256 {
 	fn decode<I: Input>(value: &mut I) -> Result<Self, Error> {
-		let mut sender_bytes: Vec<u8> = vec![0; 20];
-		match value.read(&mut sender_bytes) {
-			Ok(_) => {Ok(Hash256(Hash::from_slice(&*sender_bytes)))}
+		let mut hash_bytes: Vec<u8> = vec![0; 32];
+		match value.read(&mut hash_bytes) {
+			Ok(_) => {Ok(Hash256(Hash::from_slice(&*hash_bytes)))}
 
 			Err(e) => {
-				debug::error!("cant convert uin256: {}", e.what());
-				Err(Error::from("Cant decode eth address"))}
+				debug::error!("cant convert hash256: {}", e.what());
+				Err(Error::from("Cant decode hash256"))}
 		}
 	}
 }
@@ -47,3 +47,9 @@ impl From<Hash> for Hash256 {
 		Hash256(value)
 	}
 }
+
+impl From<Hash256> for Hash {
+	fn from(value: Hash256) -> Self {
+		value.0
+	}
+}
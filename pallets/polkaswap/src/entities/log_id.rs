@@ -0,0 +1,19 @@
+use codec::{Encode, Decode};
+use sp_std::fmt::{Display, Formatter};
+use sp_std::fmt;
+
+use crate::entities::Hash256;
+
+/// Identifier of ethereum log which emitted contract method
+/// Log index is unique within block, so with transaction hash it identifies log on the chain
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Copy, Clone, Default)]
+pub struct LogId {
+	pub tx_hash: Hash256,
+	pub log_index: u32,
+}
+
+impl Display for LogId {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}", self.tx_hash, self.log_index)
+	}
+}
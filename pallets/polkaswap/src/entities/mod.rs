@@ -1,16 +1,28 @@
 pub use block_event::BlockEvents;
-pub use contract_method::ContractMethod;
+pub use contract_method::{ContractMethod, ContractMethodKind};
 pub use eth_address::EthAddress;
 pub use hash256::Hash256;
+pub use log_id::LogId;
+pub use pool::{LiquidityPosition, Pool, SwapDirection, UserBalances, UserTokenBalance};
+pub use price::Price;
 pub use sender_amount::SenderAmount;
+pub use swap_order::SwapOrder;
+pub use sync_status::SyncStatus;
 pub use uint256::Uint256;
+pub use withdrawal::{Withdrawal, WithdrawalBundle, WithdrawalProof};
 
 pub use crate::eth_bridge::payloads::erc20_transfer::ERC20Event;
 
 pub mod contract_method;
 pub mod sender_amount;
+pub mod swap_order;
 mod block_event;
 pub mod eth_address;
 pub mod hash256;
+pub mod log_id;
+pub mod pool;
+pub mod price;
+pub mod sync_status;
 pub mod uint256;
+pub mod withdrawal;
 
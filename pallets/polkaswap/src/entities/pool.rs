@@ -0,0 +1,55 @@
+use codec::{Encode, Decode};
+use sp_std::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{EthAddress, Price, Uint256};
+
+/// State of ETH/token pool
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct Pool {
+	pub token: EthAddress,
+	pub token_reserve: Uint256,
+	pub eth_reserve: Uint256,
+	/// Total supply of liquidity tokens
+	pub total_supply: Uint256,
+	/// Spot price, None for empty pool
+	pub price: Option<Price>,
+}
+
+/// Balances of user in the pool of token
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct UserTokenBalance {
+	pub token: EthAddress,
+	pub balance: Uint256,
+	/// Liquidity tokens of the pool
+	pub liquidity: Uint256,
+}
+
+/// Balances of user: ETH and all registered tokens
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct UserBalances {
+	pub eth: Uint256,
+	pub tokens: Vec<UserTokenBalance>,
+}
+
+/// Pool reserves which liquidity provider would get for removing its liquidity
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct LiquidityPosition {
+	pub token: EthAddress,
+	pub liquidity: Uint256,
+	pub token_amount: Uint256,
+	pub eth_amount: Uint256,
+}
+
+/// Direction of swap in ETH/token pool
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum SwapDirection {
+	EthToToken,
+	TokenToEth,
+}
@@ -0,0 +1,119 @@
+use codec::{Encode, Decode};
+use sp_std::fmt::{Display, Formatter};
+use sp_std::fmt;
+
+use crate::entities::Uint256;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Fixed point precision of price, 18 decimals the same as ETH
+pub const PRICE_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// Decimals of ETH amounts (wei)
+pub const ETH_DECIMALS: u32 = 18;
+
+/// Price of ETH in token base units: token base units per wei, scaled by PRICE_PRECISION
+/// Pools with 6 decimals tokens have prices far below 1, so integer ratio can't be used
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Copy, Clone, Default)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Price(Uint256);
+
+impl Price {
+	/// Spot price of the pool, None if there is no ETH in the pool or on overflow
+	pub fn from_reserves(token_reserve: Uint256, eth_reserve: Uint256) -> Option<Price> {
+		token_reserve.checked_mul(Uint256::from(PRICE_PRECISION))?
+			.checked_div(eth_reserve)
+			.map(Price)
+	}
+
+	/// Price from integer number of whole tokens per 1 ETH, the inverse of to_decimal
+	pub fn from_ratio(ratio: u128, token_decimals: u32) -> Option<Price> {
+		let decimal = Uint256::from(ratio).checked_mul(Uint256::from(PRICE_PRECISION))?;
+		if token_decimals > ETH_DECIMALS {
+			decimal.checked_mul(pow10(token_decimals - ETH_DECIMALS)?).map(Price)
+		} else {
+			decimal.checked_div(pow10(ETH_DECIMALS - token_decimals)?).map(Price)
+		}
+	}
+
+	/// Token amount which costs eth_amount
+	pub fn token_amount(&self, eth_amount: Uint256) -> Option<Uint256> {
+		eth_amount.checked_mul(self.0)?.checked_div(Uint256::from(PRICE_PRECISION))
+	}
+
+	/// ETH amount which costs token_amount, None for zero price
+	pub fn eth_amount(&self, token_amount: Uint256) -> Option<Uint256> {
+		token_amount.checked_mul(Uint256::from(PRICE_PRECISION))?.checked_div(self.0)
+	}
+
+	/// Price of 1 ETH in whole tokens, scaled by PRICE_PRECISION
+	pub fn to_decimal(&self, token_decimals: u32) -> Option<Uint256> {
+		if token_decimals > ETH_DECIMALS {
+			self.0.checked_div(pow10(token_decimals - ETH_DECIMALS)?)
+		} else {
+			self.0.checked_mul(pow10(ETH_DECIMALS - token_decimals)?)
+		}
+	}
+
+	pub fn into_inner(self) -> Uint256 {
+		self.0
+	}
+}
+
+impl Display for Price {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+fn pow10(exp: u32) -> Option<Uint256> {
+	(0..exp).try_fold(Uint256::from(1), |acc, _| acc.checked_mul(Uint256::from(10)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ETH: u128 = PRICE_PRECISION;
+	const USDC: u128 = 1_000_000;
+
+	#[test]
+	fn six_decimals_token_pool() {
+		// 100 ETH / 200_000 USDC, integer ratio would be 0 here
+		let price = Price::from_reserves(Uint256::from(200_000 * USDC), Uint256::from(100 * ETH)).unwrap();
+
+		assert_eq!(price.to_decimal(6), Some(Uint256::from(2000 * ETH)));
+		assert_eq!(price.token_amount(Uint256::from(ETH)), Some(Uint256::from(2000 * USDC)));
+		assert_eq!(price.eth_amount(Uint256::from(2000 * USDC)), Some(Uint256::from(ETH)));
+	}
+
+	#[test]
+	fn skewed_pools() {
+		// 1 token base unit for 1 ETH
+		let price = Price::from_reserves(Uint256::from(1), Uint256::from(ETH)).unwrap();
+		assert_eq!(price.into_inner(), Uint256::from(1));
+		assert_eq!(price.token_amount(Uint256::from(ETH)), Some(Uint256::from(1)));
+		assert_eq!(price.eth_amount(Uint256::from(1)), Some(Uint256::from(ETH)));
+
+		// 18 decimals token which is much more expensive than ETH
+		let price = Price::from_reserves(Uint256::from(ETH), Uint256::from(1000 * ETH)).unwrap();
+		assert_eq!(price.to_decimal(18), Some(Uint256::from(ETH / 1000)));
+
+		// Tokens with more decimals than ETH
+		let price = Price::from_reserves(Uint256::from(3 * 100_000_000_000_000_000_000u128), Uint256::from(ETH)).unwrap();
+		assert_eq!(price.to_decimal(20), Some(Uint256::from(3 * ETH)));
+	}
+
+	#[test]
+	fn empty_pool() {
+		assert_eq!(Price::from_reserves(Uint256::from(1000), Uint256::from(0)), None);
+		assert_eq!(Price::default().eth_amount(Uint256::from(1000)), None);
+		assert_eq!(Price::from_ratio(1000, 18).unwrap().token_amount(Uint256::from(2)), Some(Uint256::from(2000)));
+
+		// Initial ratio is in whole tokens, so it doesn't depend on token decimals
+		let price = Price::from_ratio(1000, 6).unwrap();
+		assert_eq!(price.token_amount(Uint256::from(ETH)), Some(Uint256::from(1000 * USDC)));
+		assert_eq!(price.to_decimal(6), Some(Uint256::from(1000 * ETH)));
+		assert_eq!(Price::from_ratio(3, 20).unwrap().to_decimal(20), Some(Uint256::from(3 * ETH)));
+	}
+}
@@ -36,3 +36,31 @@ impl TryFrom<Log> for SenderAmount {
 		Ok(SenderAmount { sender: sender.into(), amount: amount.into() })
 	}
 }
+
+impl SenderAmount {
+	/// Converts log with (sender, token, value) params
+	/// @return token contract address and SenderAmount
+	pub fn try_from_token_log(value: Log) -> Result<(EthAddress, SenderAmount), ConvertError> {
+
+		if value.params.len() != 3 {
+			return Err(CantConvertFrom)
+		}
+
+		let sender = match value.params[0].value {
+			Token::Address(addr) => addr,
+			_ => return Err(CantConvertFrom)
+		};
+
+		let token = match value.params[1].value {
+			Token::Address(addr) => addr,
+			_ => return Err(CantConvertToken)
+		};
+
+		let amount = match value.params[2].value {
+			Token::Uint(v) => v,
+			_ => return Err(CantConvertAmount)
+		};
+
+		Ok((token.into(), SenderAmount { sender: sender.into(), amount: amount.into() }))
+	}
+}
@@ -0,0 +1,64 @@
+use ethabi::{Log, Token};
+use core::{ convert::*, fmt::Debug};
+use codec::{Encode, Decode};
+use sp_std::{
+	prelude::*,
+};
+
+use crate::errors::{ConvertError, ConvertError::*};
+use crate::entities::{EthAddress, Uint256};
+
+/// Swap with slippage limit
+/// For exact input swaps amount is input and limit is minimal output,
+/// for exact output swaps amount is output and limit is maximal input
+/// Deadline is the last ethereum block number where swap could be included
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Copy, Clone)]
+pub struct SwapOrder {
+	pub sender: EthAddress,
+	pub amount: Uint256,
+	pub limit: Uint256,
+	pub deadline: Uint256,
+}
+
+impl SwapOrder {
+	/// Converts log with (sender, token, amount, limit, deadline) params
+	/// @return token contract address and SwapOrder
+	pub fn try_from_token_log(value: Log) -> Result<(EthAddress, SwapOrder), ConvertError> {
+
+		if value.params.len() != 5 {
+			return Err(CantConvertFrom)
+		}
+
+		let sender = match value.params[0].value {
+			Token::Address(addr) => addr,
+			_ => return Err(CantConvertFrom)
+		};
+
+		let token = match value.params[1].value {
+			Token::Address(addr) => addr,
+			_ => return Err(CantConvertToken)
+		};
+
+		let amount = match value.params[2].value {
+			Token::Uint(v) => v,
+			_ => return Err(CantConvertAmount)
+		};
+
+		let limit = match value.params[3].value {
+			Token::Uint(v) => v,
+			_ => return Err(CantConvertLimit)
+		};
+
+		let deadline = match value.params[4].value {
+			Token::Uint(v) => v,
+			_ => return Err(CantConvertDeadline)
+		};
+
+		Ok((token.into(), SwapOrder {
+			sender: sender.into(),
+			amount: amount.into(),
+			limit: limit.into(),
+			deadline: deadline.into(),
+		}))
+	}
+}
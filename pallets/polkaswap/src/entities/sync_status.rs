@@ -0,0 +1,16 @@
+use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// State of ethereum sync
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct SyncStatus {
+	pub last_synced_block: u32,
+	/// None if nothing is synced and start block is not configured
+	pub next_block: Option<u32>,
+	pub start_block: u32,
+	pub confirmation_depth: u32,
+	/// Sync is halted on ethereum reorg till root rolls it back
+	pub halted: bool,
+}
@@ -5,10 +5,11 @@ use core::cmp::{Ord, Ordering};
 use ethabi::Uint;
 use sp_std::fmt::{Display, Formatter};
 use sp_std::{fmt, prelude::*};
-use sp_std::ops::{Add, Sub, Div, Mul};
 use frame_support::traits::IsType;
 use hex::encode;
 use sp_std::str::FromStr;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
 pub struct Uint256(Uint);
@@ -43,21 +44,94 @@ impl Decode for Uint256 {
 	}
 }
 
+impl Uint256 {
+	/// Big endian bytes, the same as Solidity `abi.encodePacked(uint256)`
+	pub fn to_big_endian(&self) -> [u8; 32] {
+		let mut res = [0u8; 32];
+		self.0.to_big_endian(&mut res);
+		res
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.0.is_zero()
+	}
+
+	/// None on overflow
+	pub fn checked_add(self, rhs: Self) -> Option<Self> {
+		self.0.checked_add(rhs.0).map(Uint256)
+	}
+
+	/// None on underflow
+	pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+		self.0.checked_sub(rhs.0).map(Uint256)
+	}
+
+	/// None on overflow
+	pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+		self.0.checked_mul(rhs.0).map(Uint256)
+	}
+
+	/// None on division by zero
+	pub fn checked_div(self, rhs: Self) -> Option<Self> {
+		self.0.checked_div(rhs.0).map(Uint256)
+	}
+
+	pub fn saturating_add(self, rhs: Self) -> Self {
+		Uint256(self.0.saturating_add(rhs.0))
+	}
+
+	pub fn saturating_sub(self, rhs: Self) -> Self {
+		Uint256(self.0.saturating_sub(rhs.0))
+	}
+
+	pub fn saturating_mul(self, rhs: Self) -> Self {
+		Uint256(self.0.saturating_mul(rhs.0))
+	}
+
+	/// Largest integer which square is not greater than value
+	pub fn integer_sqrt(self) -> Self {
+		Uint256(self.0.integer_sqrt())
+	}
+}
+
 impl Display for Uint256 {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		write!(f, "{}", self.0)
 	}
 }
 
+/// Serialized as decimal string, so JavaScript clients don't lose precision of 256-bit amounts
+#[cfg(feature = "std")]
+impl Serialize for Uint256 {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+		serializer.collect_str(&self.0)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for Uint256 {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+		let s = String::deserialize(deserializer)?;
+		Uint::from_dec_str(&s)
+			.map(Uint256)
+			.map_err(|_| serde::de::Error::custom("Cant convert decimal str to Uint256"))
+	}
+}
+
 impl From<Uint> for Uint256 {
 	fn from(value: Uint) -> Self {
 		Uint256(value)
 	}
 }
 
+/// Values which don't fit into u128 are saturated
 impl From<Uint256> for u128 {
 	fn from(value: Uint256) -> Self {
-		value.0.as_u128()
+		if value.0 > Uint::from(u128::max_value()) {
+			u128::max_value()
+		} else {
+			value.0.as_u128()
+		}
 	}
 }
 
@@ -73,38 +147,6 @@ impl From<u128> for Uint256 {
 	}
 }
 
-impl Add for Uint256 {
-	type Output = Uint256;
-
-	fn add(self, rhs: Self) -> Self::Output {
-		(rhs.0 + self.0).into()
-	}
-}
-
-impl Sub for Uint256 {
-	type Output = Uint256;
-
-	fn sub(self, rhs: Self) -> Self::Output {
-		(self.0 - rhs.0).into()
-	}
-}
-
-impl Mul for Uint256 {
-	type Output = Uint256;
-
-	fn mul(self, rhs: Self) -> Self::Output {
-		(self.0 * rhs.0).into()
-	}
-}
-
-impl Div for Uint256 {
-	type Output = Uint256;
-
-	fn div(self, rhs: Self) -> Self::Output {
-		(self.0 / rhs.0).into()
-	}
-}
-
 impl Ord for Uint256 {
 	fn cmp(&self, other: &Self) -> Ordering {
 		self.0.cmp(&other.0)
@@ -143,4 +185,20 @@ mod tests {
 		let dec = Uint256(Uint::from_little_endian(enc.as_slice()));
 		assert_eq!(src, dec);
 	}
+
+	#[test]
+	fn checked_arithmetic() {
+		let max = Uint256(Uint::max_value());
+		let one = Uint256::from(1);
+
+		assert_eq!(max.checked_add(one), None);
+		assert_eq!(one.checked_sub(max), None);
+		assert_eq!(max.checked_mul(Uint256::from(2)), None);
+		assert_eq!(one.checked_div(Uint256::from(0)), None);
+		assert_eq!(Uint256::from(6).checked_div(Uint256::from(3)), Some(Uint256::from(2)));
+
+		assert_eq!(max.saturating_add(one), max);
+		assert_eq!(one.saturating_sub(max), Uint256::from(0));
+		assert_eq!(u128::from(max), u128::max_value());
+	}
 }
@@ -0,0 +1,70 @@
+use codec::{Encode, Decode};
+use sp_core::H256;
+use sp_std::prelude::*;
+
+use crate::entities::{EthAddress, Uint256};
+use crate::eth_bridge::merkle::keccak_256;
+
+/// Withdrawal which could be claimed on Ethereum vault contract
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+pub struct Withdrawal {
+	pub nonce: u64,
+	pub recipient: EthAddress,
+	/// Token contract address, zero address for ETH
+	pub asset: EthAddress,
+	pub amount: Uint256,
+	/// Vault contract at the time withdrawal was queued, validators sign withdrawal for it
+	pub vault: EthAddress,
+}
+
+impl Withdrawal {
+	/// Leaf of withdrawals merkle tree
+	/// keccak256(abi.encodePacked(uint256 nonce, address recipient, address asset, uint256 amount))
+	pub fn leaf_hash(&self) -> H256 {
+		let mut data: Vec<u8> = Vec::with_capacity(104);
+		data.extend_from_slice(&Uint256::from(self.nonce as u128).to_big_endian());
+		data.extend_from_slice(&self.recipient.encode());
+		data.extend_from_slice(&self.asset.encode());
+		data.extend_from_slice(&self.amount.to_big_endian());
+		keccak_256(&data)
+	}
+
+	/// Message which is signed by validators
+	/// keccak256(abi.encodePacked(address vault, uint256 nonce, address recipient, address asset, uint256 amount))
+	pub fn message_hash(&self) -> H256 {
+		let mut data: Vec<u8> = Vec::with_capacity(124);
+		data.extend_from_slice(&self.vault.encode());
+		data.extend_from_slice(&Uint256::from(self.nonce as u128).to_big_endian());
+		data.extend_from_slice(&self.recipient.encode());
+		data.extend_from_slice(&self.asset.encode());
+		data.extend_from_slice(&self.amount.to_big_endian());
+		keccak_256(&data)
+	}
+
+	/// Digest which is recovered by `ecrecover` in vault contract
+	/// keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", message_hash))
+	pub fn eth_signed_message_hash(&self) -> H256 {
+		let mut data: Vec<u8> = b"\x19Ethereum Signed Message:\n32".to_vec();
+		data.extend_from_slice(self.message_hash().as_bytes());
+		keccak_256(&data)
+	}
+}
+
+/// Withdrawal with merkle proof against root of L2 block where it was queued
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+pub struct WithdrawalProof<BlockNumber> {
+	pub withdrawal: Withdrawal,
+	pub block_number: BlockNumber,
+	pub root: H256,
+	pub proof: Vec<H256>,
+}
+
+/// Withdrawal with validators signatures, ready to be submitted to vault contract
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
+pub struct WithdrawalBundle {
+	pub withdrawal: Withdrawal,
+	pub vault: EthAddress,
+	pub signers: Vec<EthAddress>,
+	/// 65 bytes signatures (r, s, v) with v = 27 | 28
+	pub signatures: Vec<Vec<u8>>,
+}
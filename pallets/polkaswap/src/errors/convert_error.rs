@@ -3,7 +3,10 @@ use core::{fmt, fmt::{Debug,Formatter}};
 pub enum ConvertError {
 	CantConvertFrom,
 	CantConvertTo,
+	CantConvertToken,
 	CantConvertAmount,
+	CantConvertLimit,
+	CantConvertDeadline,
 }
 
 impl Debug for ConvertError {
@@ -11,7 +14,10 @@ impl Debug for ConvertError {
 		match self {
 			ConvertError::CantConvertFrom => write!(f, "Cant convert from field"),
 			ConvertError::CantConvertTo => write!(f, "Cant convert to field"),
-			ConvertError::CantConvertAmount => write!(f, "Cant convert amount field")
+			ConvertError::CantConvertToken => write!(f, "Cant convert token field"),
+			ConvertError::CantConvertAmount => write!(f, "Cant convert amount field"),
+			ConvertError::CantConvertLimit => write!(f, "Cant convert limit field"),
+			ConvertError::CantConvertDeadline => write!(f, "Cant convert deadline field")
 		}
 	}
 }
@@ -1,50 +1,113 @@
 use core::convert::*;
 
 use ethabi::{Address, Event, EventParam, Hash, ParamType, RawLog};
-use frame_support::debug;
+use frame_support::{debug, IterableStorageMap, StorageMap};
 use sha3::{Digest, Keccak256};
 use sp_std::collections::btree_map::BTreeMap;
 use sp_std::prelude::*;
-use sp_std::str::FromStr;
 
-use crate::{TOKEN_CONTRACT_ADDRESS, VAULT_CONTRACT_ADDRESS};
-use crate::{Error, Module, Trait};
-use crate::entities::{BlockEvents, ContractMethod, EthAddress, SenderAmount, Uint256};
+use crate::{Error, Module, Tokens, Trait};
+use crate::entities::{BlockEvents, ContractMethod, EthAddress, LogId, SenderAmount, Uint256};
 use crate::eth_bridge::vault::EventVaultParser;
 
-use super::payloads::{ERC20Event, FromTxLog};
+use super::payloads::{ERC20Event, EthBlockHeader, FromTxLog, TxLog};
 
 // ERC20 TOKEN TRANSFER
 const EVENT_ERC20_TRANSFER: &[u8] = b"Transfer(address,address,uint256)";
 
 impl<T: Trait> Module<T> {
-	pub(crate) fn get_block_events(block_number: u32) -> Result<BlockEvents, Error<T>> {
-		let fetched_events = Self::fetch_events(block_number)?;
+	/// Returns events of consecutive blocks, blocks without events are included too,
+	/// so their hashes link the chain
+	pub(crate) fn get_blocks_events(from_block: u32, to_block: u32) -> Result<Vec<BlockEvents>, Error<T>> {
+		// Headers are fetched first, logs should belong to the same blocks
+		let mut headers: Vec<EthBlockHeader> = vec![];
+		for block_number in from_block..=to_block {
+			let header = Self::get_eth_block_header(block_number)?;
+			if let Some(prev) = headers.last() {
+				if header.parent_hash != prev.hash {
+					debug::warn!("Eth block {} is not linked to the previous one", block_number);
+					return Err(<Error<T>>::EthBlockHashMismatch);
+				}
+			}
+			headers.push(header);
+		}
 
+		let fetched_events = Self::fetch_bridge_logs(from_block, to_block)?;
 		debug::info!("Fetched {} events", fetched_events.len());
 
-		let mut result: Vec<ContractMethod> = vec![];
+		// Logs are grouped by block number, provider returns them ordered by block and log index
+		let mut logs_by_block: BTreeMap<u32, Vec<&TxLog>> = BTreeMap::new();
+		for tx_log in &fetched_events {
+			logs_by_block.entry(tx_log.block_number).or_insert_with(Vec::new).push(tx_log);
+		}
+
+		let mut result = vec![];
+		for header in headers {
+			let logs = logs_by_block.remove(&header.number).unwrap_or_default();
+			result.push(Self::parse_block_events(&header, logs)?);
+		}
+		Ok(result)
+	}
+
+	/// Fetches vault events and transfers of registered tokens to the vault,
+	/// logs are filtered by provider and returned ordered by block and log index
+	fn fetch_bridge_logs(from_block: u32, to_block: u32) -> Result<Vec<TxLog>, Error<T>> {
+		let vault_contract_address: Address = Self::vault_contract_address().into();
+
+		let vault_topics = EventVaultParser::new().topics();
+		let mut logs = Self::fetch_events(
+			vec![vault_contract_address],
+			vec![Some(vault_topics)],
+			from_block,
+			to_block,
+		)?;
+
+		let tokens: Vec<Address> = Tokens::iter().map(|(token, _)| token.into()).collect();
+		if !tokens.is_empty() {
+			// Transfer(from, to, value): vault is matched as indexed `to`
+			let vault_topic = Hash::from(vault_contract_address);
+			let mut transfers = Self::fetch_events(
+				tokens,
+				vec![Some(vec![get_topic_hash(EVENT_ERC20_TRANSFER)]), None, Some(vec![vault_topic])],
+				from_block,
+				to_block,
+			)?;
+			logs.append(&mut transfers);
+		}
+
+		logs.sort_by_key(|tx_log| (tx_log.block_number, tx_log.log_index));
+		Ok(logs)
+	}
+
+	fn parse_block_events(header: &EthBlockHeader, logs: Vec<&TxLog>) -> Result<BlockEvents, Error<T>> {
+		let mut result: Vec<(LogId, ContractMethod)> = vec![];
 
 		let vault_parser = EventVaultParser::new();
 
-		// VAULT CONTRACT ADDRESS CONVERSATION
-		let vault_contract_address = Address::from_str(VAULT_CONTRACT_ADDRESS)
-			.map_err(|_| <Error<T>>::EventParsingError)
-			.unwrap();
+		// VAULT CONTRACT ADDRESS & TOKENS ARE CONFIGURED ON-CHAIN
+		let vault_contract_address: Address = Self::vault_contract_address().into();
 
-		// TOKEN CONTRACT ADDRESS CONVERSATION
-		let token_contract_address = Address::from_str(TOKEN_CONTRACT_ADDRESS)
-			.map_err(|_| <Error<T>>::EventParsingError)
-			.unwrap();
+		debug::info!("Got {} events in block {}:", logs.len(), header.number);
+		for tx_log in logs {
+			// Logs removed due to chain reorganization are not applied
+			if tx_log.removed {
+				debug::warn!("Skipping removed log from block {}", tx_log.block_number);
+				continue;
+			}
+
+			// Provider switched to another fork between requests, block will be fetched again
+			if tx_log.block_hash != header.hash {
+				debug::warn!("Log block hash {:?} differs from block hash {:?}", tx_log.block_hash, header.hash);
+				return Err(<Error<T>>::EthBlockHashMismatch);
+			}
 
-		debug::info!("Got {} events:", fetched_events.len());
-		for tx_log in &fetched_events {
 			if tx_log.topics.len() == 0 {
 				continue;
 			}
 
 			debug::info!("{:?}", tx_log.topics);
 
+			let log_id = LogId { tx_hash: tx_log.transaction_hash.into(), log_index: tx_log.log_index };
 			let topic = tx_log.topics[0].clone();
 			let address = tx_log.address.clone();
 			let raw_log = RawLog::from_tx(tx_log);
@@ -53,22 +116,24 @@ impl<T: Trait> Module<T> {
 				debug::info!("parsing vault event");
 				if let Some(cmd) = vault_parser.parse(&topic, raw_log) {
 					debug::info!("Parsed event: {:?}", cmd);
-					result.push(cmd);
+					result.push((log_id, cmd));
 				}
-			} else if address == token_contract_address
-				&& topic == get_topic_hash(EVENT_ERC20_TRANSFER)
+			} else if topic == get_topic_hash(EVENT_ERC20_TRANSFER)
+				&& Tokens::contains_key(EthAddress::from(address))
 			{
 				debug::info!("parsing token contract event");
 				if let Some(cmd) =
-				parse_token_transfer_event(raw_log, &vault_contract_address)
+				parse_token_transfer_event(raw_log, &address, &vault_contract_address)
 				{
-					result.push(cmd);
+					result.push((log_id, cmd));
 				}
 			};
 		}
 
 		Ok(BlockEvents {
-			block_number,
+			block_number: header.number,
+			block_hash: header.hash.into(),
+			parent_hash: header.parent_hash.into(),
 			methods: result,
 		})
 	}
@@ -76,6 +141,7 @@ impl<T: Trait> Module<T> {
 
 fn parse_token_transfer_event(
 	raw_log: RawLog,
+	token_address: &Address,
 	vault_address: &Address,
 ) -> Option<ContractMethod> {
 	let event = Event {
@@ -104,7 +170,7 @@ fn parse_token_transfer_event(
 		Ok(log) => {
 			if let Ok(erc20event) = ERC20Event::try_from(log) {
 				if erc20event.to == *vault_address {
-					Some(ContractMethod::DepositToken(SenderAmount {
+					Some(ContractMethod::DepositToken(token_address.clone().into(), SenderAmount {
 						sender: erc20event.from.into(),
 						amount: erc20event.amount.into(),
 					}))
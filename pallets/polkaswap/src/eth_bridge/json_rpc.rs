@@ -1,16 +1,17 @@
 use frame_support::{debug, traits::Get};
-use sp_runtime::offchain;
+use sp_runtime::offchain::{self, storage::StorageValueRef};
 use sp_std::prelude::*;
 use sp_std::str;
 
 // We use `alt_serde`, and Xanewok-modified `serde_json` so that we can compile the program
 // with serde(features `std`) and alt_serde(features `no_std`).
 use alt_serde::{ Serialize};
+use ethabi::{Address, Hash};
 
-use super::payloads::{EthBlockNumberResponse, JSONRpcRequest, TxLog, EthGetLogsResponse, EthGetLogsRequest};
+use super::payloads::{EthBlockHeader, EthBlockNumberResponse, EthGetBlockByNumberRequest, EthGetBlockByNumberResponse,
+					  JSONRpcRequest, TxLog, EthGetLogsResponse, EthGetLogsRequest};
 
-pub const FETCH_TIMEOUT_PERIOD: u64 = 30000;
-use crate::{Error, Module, Trait};
+use crate::{Error, FETCH_TIMEOUT_PERIOD, LS_ETH_PROVIDER_URLS_KEY, Module, Trait};
 
 
 // ETHEREUM INTERCONNECTION MODULE
@@ -31,21 +32,52 @@ impl<T: Trait> Module<T> {
 			.map_err(|_| <Error<T>>::HttpFetchingError)?;
 
 		// debug::info!("Eth last block response: {}", resp_str);
-		let response: EthBlockNumberResponse = serde_json::from_str(resp_str).unwrap();
+		let response: EthBlockNumberResponse = serde_json::from_str(resp_str)
+			.map_err(|_| {
+				debug::error!("cant parse last eth block response: {}", resp_str);
+				<Error<T>>::HttpFetchingError
+			})?;
 		Ok(response.result)
 	}
 
-	pub(crate) fn fetch_events(from_block: u32) -> Result<Vec<TxLog>, Error<T>> {
+	// Returns header of Ethereum block, it's used to link synced blocks by hashes
+	pub(crate) fn get_eth_block_header(block_number: u32) -> Result<EthBlockHeader, Error<T>> {
+		let params = EthGetBlockByNumberRequest(block_number, false);
+
+		let resp_bytes = Self::make_rpc_request("eth_getBlockByNumber", params)
+			.map_err(|e| {
+				debug::error!("cant fetch eth block: {} {:?}", block_number, e);
+				<Error<T>>::HttpFetchingError
+			})?;
+
+		// Convert bytes into &str
+		let resp_str = str::from_utf8(&resp_bytes)
+			.map_err(|_| <Error<T>>::HttpFetchingError)?;
+
+		let response: EthGetBlockByNumberResponse = serde_json::from_str(resp_str)
+			.map_err(|_| <Error<T>>::EventParsingError)?;
+		response.result.ok_or(<Error<T>>::EthBlockNotFound)
+	}
+
+	// Returns logs of blocks range which match filter, both bounds are included
+	pub(crate) fn fetch_events(
+		address: Vec<Address>,
+		topics: Vec<Option<Vec<Hash>>>,
+		from_block: u32,
+		to_block: u32,
+	) -> Result<Vec<TxLog>, Error<T>> {
 		let params = EthGetLogsRequest {
-			from_block: from_block,
-			to_block: from_block+1,
+			address,
+			topics,
+			from_block,
+			to_block,
 		};
 
 		debug::info!("Ser:{}", serde_json::to_string(&params).unwrap());
 
 		let resp_bytes = Self::make_rpc_request("eth_getLogs", &[params])
 			.map_err(|e| {
-				debug::error!("cant fetch logs from block: {} {:?}", from_block, e);
+				debug::error!("cant fetch logs from blocks: {}..{} {:?}", from_block, to_block, e);
 				<Error<T>>::HttpFetchingError
 			})?;
 
@@ -54,11 +86,15 @@ impl<T: Trait> Module<T> {
 			.map_err(|_| <Error<T>>::EventParsingError)?;
 
 		debug::info!("Eth logs response: {}", resp_str);
-		let response: EthGetLogsResponse = serde_json::from_str(resp_str).unwrap();
+		let response: EthGetLogsResponse = serde_json::from_str(resp_str)
+			.map_err(|_| {
+				debug::error!("cant parse logs response from blocks: {}..{}", from_block, to_block);
+				<Error<T>>::HttpFetchingError
+			})?;
 		Ok(response.result)
 	}
 
-	// Make an rpc request to JSON RPC provider
+	// Make an rpc request to JSON RPC providers, they are tried in order until one responds
 	fn make_rpc_request<P>(method: &'static str, params: P) -> Result<Vec<u8>, Error<T>>
 		where P: Serialize {
 		let body = JSONRpcRequest {
@@ -69,12 +105,46 @@ impl<T: Trait> Module<T> {
 		};
 
 		let body = serde_json::to_string(&body).expect("Cant marshal");
-		let eth_provider_url = T::EthProviderEndpoint::get();
 
+		let mut result = Err(<Error<T>>::HttpFetchingError);
+		for url in Self::eth_provider_urls() {
+			let eth_provider_url = match str::from_utf8(&url) {
+				Ok(url) => url,
+				Err(_) => {
+					debug::error!("invalid eth provider url: {:?}", url);
+					continue;
+				}
+			};
+
+			result = Self::send_rpc_request(eth_provider_url, &body);
+			if result.is_ok() {
+				break;
+			}
+			debug::warn!("eth provider {} failed, trying the next one", eth_provider_url);
+		}
+		result
+	}
+
+	// Urls set by node with `--eth-rpc-url`, or the default endpoint of runtime
+	pub(crate) fn eth_provider_urls() -> Vec<Vec<u8>> {
+		let urls = StorageValueRef::persistent(LS_ETH_PROVIDER_URLS_KEY)
+			.get::<Vec<Vec<u8>>>()
+			.flatten()
+			.unwrap_or_default();
+
+		if urls.is_empty() {
+			vec![T::EthProviderEndpoint::get().as_bytes().to_vec()]
+		} else {
+			urls
+		}
+	}
+
+	// Post JSON RPC request body to provider and return response body
+	fn send_rpc_request(eth_provider_url: &str, body: &str) -> Result<Vec<u8>, Error<T>> {
 		let body = vec![body];
 		let request = offchain::http::Request::post(eth_provider_url, body);
 
-		// Keeping the offchain worker execution time reasonable, so limiting the call to be within 3s.
+		// Keeping the offchain worker execution time reasonable, so limiting the call to be within FETCH_TIMEOUT_PERIOD
 		let timeout = sp_io::offchain::timestamp()
 			.add(offchain::Duration::from_millis(FETCH_TIMEOUT_PERIOD));
 
@@ -0,0 +1,86 @@
+use sha3::{Digest, Keccak256};
+use sp_core::H256;
+use sp_std::prelude::*;
+
+/// Keccak256 hash, the same as Solidity `keccak256`
+pub fn keccak_256(data: &[u8]) -> H256 {
+	H256::from_slice(&*Keccak256::digest(data))
+}
+
+/// Hash of pair of nodes, pair is sorted before hashing.
+/// It's compatible with OpenZeppelin `MerkleProof.verify`
+fn hash_pair(a: &H256, b: &H256) -> H256 {
+	let (left, right) = if a <= b { (a, b) } else { (b, a) };
+	let mut data: Vec<u8> = Vec::with_capacity(64);
+	data.extend_from_slice(left.as_bytes());
+	data.extend_from_slice(right.as_bytes());
+	keccak_256(&data)
+}
+
+/// Builds next level of tree, node without pair is moved to the next level as is
+fn next_level(level: &[H256]) -> Vec<H256> {
+	level.chunks(2)
+		.map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+		.collect()
+}
+
+/// Merkle root of leaves, zero hash for empty list
+pub fn merkle_root(leaves: &[H256]) -> H256 {
+	if leaves.is_empty() {
+		return H256::zero();
+	}
+
+	let mut level = leaves.to_vec();
+	while level.len() > 1 {
+		level = next_level(&level);
+	}
+	level[0]
+}
+
+/// Merkle proof for leaf with provided index
+/// @return sibling hashes from leaf to root
+pub fn merkle_proof(leaves: &[H256], index: usize) -> Vec<H256> {
+	let mut proof: Vec<H256> = vec![];
+	let mut level = leaves.to_vec();
+	let mut index = index;
+
+	while level.len() > 1 {
+		let sibling = index ^ 1;
+		if sibling < level.len() {
+			proof.push(level[sibling]);
+		}
+		level = next_level(&level);
+		index /= 2;
+	}
+	proof
+}
+
+/// Verifies proof the same way as vault contract does
+pub fn verify_proof(root: &H256, leaf: &H256, proof: &[H256]) -> bool {
+	let computed = proof.iter().fold(*leaf, |node, sibling| hash_pair(&node, sibling));
+	computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn proofs_are_verified_for_all_leaves() {
+		for size in 1..10u8 {
+			let leaves: Vec<H256> = (0..size).map(|i| keccak_256(&[i])).collect();
+			let root = merkle_root(&leaves);
+			for (index, leaf) in leaves.iter().enumerate() {
+				let proof = merkle_proof(&leaves, index);
+				assert!(verify_proof(&root, leaf, &proof));
+			}
+		}
+	}
+
+	#[test]
+	fn single_leaf_is_root() {
+		let leaf = keccak_256(b"withdrawal");
+		assert_eq!(merkle_root(&[leaf]), leaf);
+		assert!(merkle_proof(&[leaf], 0).is_empty());
+	}
+}
@@ -1,4 +1,5 @@
 pub mod json_rpc;
 pub mod event_parser;
+pub mod merkle;
 pub mod payloads;
 mod vault;
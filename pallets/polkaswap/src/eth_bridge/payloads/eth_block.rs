@@ -1,7 +1,8 @@
 use sp_std::prelude::*;
 // We use `alt_serde`, and Xanewok-modified `serde_json` so that we can compile the program
 // with serde(features `std`) and alt_serde(features `no_std`).
-use alt_serde::Deserialize;
+use alt_serde::{Deserialize, Serialize};
+use ethabi::Hash;
 use super::serde_helpers::*;
 
 // Payload for JSON RPCRequest to get the last block of Ethereum network
@@ -16,3 +17,33 @@ pub struct EthBlockNumberResponse {
 	#[serde(deserialize_with = "de_hex_to_u32")]
 	pub(crate) result: u32,
 }
+
+// Params of JSON RPCRequest to get block header: (block number, return full transactions)
+#[serde(crate = "alt_serde")]
+#[derive(Serialize)]
+pub struct EthGetBlockByNumberRequest(
+	#[serde(serialize_with = "ser_u32_to_hex")]
+	pub(crate) u32,
+	pub(crate) bool,
+);
+
+// Block header fields which are needed to link synced blocks
+#[serde(crate = "alt_serde")]
+#[derive(Deserialize)]
+pub struct EthBlockHeader {
+	#[serde(deserialize_with = "de_hex_to_u32")]
+	pub(crate) number: u32,
+
+	#[serde(deserialize_with = "de_hex_to_hash")]
+	pub(crate) hash: Hash,
+
+	#[serde(rename = "parentHash", deserialize_with = "de_hex_to_hash")]
+	pub(crate) parent_hash: Hash,
+}
+
+// Payload for JSON RPCRequest to get block header by number
+#[serde(crate = "alt_serde")]
+#[derive(Deserialize)]
+pub struct EthGetBlockByNumberResponse {
+	pub(crate) result: Option<EthBlockHeader>,
+}
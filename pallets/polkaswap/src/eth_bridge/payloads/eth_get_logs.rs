@@ -14,13 +14,16 @@ use super::serde_helpers::*;
 #[serde(crate = "alt_serde")]
 #[derive(Serialize)]
 pub struct EthGetLogsRequest {
-	// #[serde(serialize_with = "ser_address_to_hex")]
-	// address: Address,
+	#[serde(serialize_with = "ser_addresses_to_hex")]
+	pub(crate) address: Vec<Address>,
 
-	#[serde(serialize_with = "ser_u32_to_hex")]
+	#[serde(serialize_with = "ser_topics_to_hex")]
+	pub(crate) topics: Vec<Option<Vec<Hash>>>,
+
+	#[serde(rename = "fromBlock", serialize_with = "ser_u32_to_hex")]
 	pub(crate) from_block: u32,
 
-	#[serde(serialize_with = "ser_u32_to_hex")]
+	#[serde(rename = "toBlock", serialize_with = "ser_u32_to_hex")]
 	pub(crate) to_block: u32,
 }
 
@@ -31,24 +34,25 @@ pub struct TxLog {
 	pub(crate) address: Address,
 
 	#[serde(rename = "blockHash", deserialize_with = "de_hex_to_hash")]
-	block_hash: Hash,
+	pub(crate) block_hash: Hash,
 
 	#[serde(rename = "blockNumber", deserialize_with = "de_hex_to_u32")]
-	block_number: u32,
+	pub(crate) block_number: u32,
 
 	#[serde(deserialize_with = "de_hex_to_vec_u8")]
 	pub(crate) data: Vec<u8>,
 
 	#[serde(rename = "logIndex", deserialize_with = "de_hex_to_u32")]
-	log_index: u32,
+	pub(crate) log_index: u32,
 
-	removed: bool,
+	// true if log was removed due to chain reorganization
+	pub(crate) removed: bool,
 
 	#[serde(deserialize_with = "decode_hex_hash_seq")]
 	pub(crate) topics: Vec<Hash>,
 
 	#[serde(rename = "transactionHash", deserialize_with = "de_hex_to_hash")]
-	transaction_hash: Hash,
+	pub(crate) transaction_hash: Hash,
 
 	#[serde(rename = "transactionIndex", deserialize_with = "de_hex_to_u32")]
 	transaction_index: u32,
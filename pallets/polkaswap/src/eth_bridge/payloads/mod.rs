@@ -6,7 +6,7 @@ pub mod eth_get_logs;
 pub mod json_rpc;
 pub mod erc20_transfer;
 
-pub use eth_block::EthBlockNumberResponse;
+pub use eth_block::{EthBlockHeader, EthBlockNumberResponse, EthGetBlockByNumberRequest, EthGetBlockByNumberResponse};
 pub use eth_get_logs::{EthGetLogsRequest, EthGetLogsResponse, FromTxLog, TxLog};
 pub use json_rpc::JSONRpcRequest;
 pub use erc20_transfer::ERC20Event;
@@ -1,4 +1,5 @@
-use alt_serde::{Serializer, Deserializer, Deserialize};
+use alt_serde::{Serialize, Serializer, Deserializer, Deserialize};
+use alt_serde::ser::SerializeSeq;
 use sp_std::str::{FromStr};
 use sp_std::prelude::*;
 use frame_support::{debug};
@@ -13,16 +14,53 @@ use core::{fmt, fmt::Formatter};
 pub fn ser_u32_to_hex<S>(value: &u32, ser: S) -> Result<S::Ok, S::Error>
 	where S: Serializer {
 	let hex_value = encode(value.to_be_bytes());
-	let result = ["0x", hex_value.as_str()].concat();
+	// Ethereum quantities are encoded without leading zeros
+	let hex_value = match hex_value.trim_start_matches('0') {
+		"" => "0",
+		trimmed => trimmed,
+	};
+	let result = ["0x", hex_value].concat();
 	ser.serialize_str(result.as_str())
 }
 
-// pub fn ser_address_to_hex<S>(value: &Address, ser: S) -> Result<S::Ok, S::Error>
-// 	where S: Serializer {
-// 	let hex_value = encode(value.as_bytes());
-// 	let result = ["0x", hex_value.as_str()].concat();
-// 	ser.serialize_str(result.as_str())
-// }
+// Convert addresses into array of HEX 0x strings
+pub fn ser_addresses_to_hex<S>(value: &Vec<Address>, ser: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+	let mut seq = ser.serialize_seq(Some(value.len()))?;
+	for address in value {
+		let hex_value = encode(address.as_bytes());
+		seq.serialize_element(["0x", hex_value.as_str()].concat().as_str())?;
+	}
+	seq.end()
+}
+
+// Hashes which match the same topic position, serialized as array of HEX 0x strings
+struct HexHashes<'a>(&'a [Hash]);
+
+impl<'a> Serialize for HexHashes<'a> {
+	fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+		where S: Serializer {
+		let mut seq = ser.serialize_seq(Some(self.0.len()))?;
+		for hash in self.0 {
+			let hex_value = encode(hash.as_bytes());
+			seq.serialize_element(["0x", hex_value.as_str()].concat().as_str())?;
+		}
+		seq.end()
+	}
+}
+
+// Convert topics filter into array, None matches any topic at the position
+pub fn ser_topics_to_hex<S>(value: &Vec<Option<Vec<Hash>>>, ser: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+	let mut seq = ser.serialize_seq(Some(value.len()))?;
+	for topic in value {
+		match topic {
+			Some(hashes) => seq.serialize_element(&HexHashes(hashes))?,
+			None => seq.serialize_element(&Option::<()>::None)?,
+		}
+	}
+	seq.end()
+}
 
 // DESERIALIZERS
 
@@ -1,10 +1,10 @@
-use ethabi::{Address, Event, EventParam, Hash, ParamType, RawLog};
+use ethabi::{Event, EventParam, Hash, ParamType, RawLog};
 use sp_std::collections::btree_map::BTreeMap;
 use sp_std::convert::TryFrom;
 use frame_support::debug;
 use sp_std::prelude::*;
 
-use crate::entities::{ContractMethod, SenderAmount};
+use crate::entities::{ContractMethod, EthAddress, SenderAmount, SwapOrder};
 use super::event_parser::get_topic_hash;
 
 pub struct EventVaultParser {
@@ -14,13 +14,17 @@ pub struct EventVaultParser {
 impl<'a> EventVaultParser {
 	pub fn new() -> Self {
 		let mut result = EventVaultParser { events_map: BTreeMap::new() };
-		result.add_event("DepositETH", ContractMethod::DepositETH);
-		result.add_event("WithdrawETH", ContractMethod::WithdrawETH);
-		result.add_event("WithdrawToken", ContractMethod::WithdrawToken);
-		result.add_event("SwapToToken", ContractMethod::SwapToToken);
-		result.add_event("SwapToETH", ContractMethod::SwapToETH);
-		result.add_event("AddLiquidity", ContractMethod::AddLiquidity);
-		result.add_event("RemoveLiquidity", ContractMethod::RemoveLiquidity);
+		result.add_event("DepositETH", EventMethod::Eth(ContractMethod::DepositETH));
+		result.add_event("WithdrawETH", EventMethod::Eth(ContractMethod::WithdrawETH));
+		result.add_event("WithdrawToken", EventMethod::Token(ContractMethod::WithdrawToken));
+		result.add_event("SwapToToken", EventMethod::Token(ContractMethod::SwapToToken));
+		result.add_event("SwapToETH", EventMethod::Token(ContractMethod::SwapToETH));
+		result.add_event("AddLiquidity", EventMethod::Token(ContractMethod::AddLiquidity));
+		result.add_event("RemoveLiquidity", EventMethod::Token(ContractMethod::RemoveLiquidity));
+		result.add_event("SwapExactETHForTokens", EventMethod::Swap(ContractMethod::SwapExactETHForTokens));
+		result.add_event("SwapETHForExactTokens", EventMethod::Swap(ContractMethod::SwapETHForExactTokens));
+		result.add_event("SwapExactTokensForETH", EventMethod::Swap(ContractMethod::SwapExactTokensForETH));
+		result.add_event("SwapTokensForExactETH", EventMethod::Swap(ContractMethod::SwapTokensForExactETH));
 		result
 	}
 
@@ -30,33 +34,75 @@ impl<'a> EventVaultParser {
 		} else { None }
 	}
 
-	fn add_event(&mut self, event_name: &str, method: fn(SenderAmount) -> ContractMethod) {
-		self.events_map.insert(get_vault_topic_hash(event_name),
+	/// Topic hashes of all known vault events, used to filter logs on provider side
+	pub fn topics(&self) -> Vec<Hash> {
+		self.events_map.keys().cloned().collect()
+	}
+
+	fn add_event(&mut self, event_name: &str, method: EventMethod) {
+		self.events_map.insert(get_vault_topic_hash(event_name, &method),
 							   EventCmd::new(event_name, method));
 	}
 }
 
+/// Vault events which operates with ETH only have (sender, value) params,
+/// events for token pools have (sender, token, value) params,
+/// swaps with slippage limit have (sender, token, amount, limit, deadline) params
+pub enum EventMethod {
+	Eth(fn(SenderAmount) -> ContractMethod),
+	Token(fn(EthAddress, SenderAmount) -> ContractMethod),
+	Swap(fn(EthAddress, SwapOrder) -> ContractMethod),
+}
+
+impl EventMethod {
+	fn params(&self) -> &'static [u8] {
+		match self {
+			EventMethod::Eth(_) => b"(address,uint256)",
+			EventMethod::Token(_) => b"(address,address,uint256)",
+			EventMethod::Swap(_) => b"(address,address,uint256,uint256,uint256)",
+		}
+	}
+}
+
 pub struct EventCmd {
 	event: Event,
-	method: fn(SenderAmount) -> ContractMethod,
+	method: EventMethod,
 }
 
 impl EventCmd {
-	fn new(event_name: &str, method: fn(SenderAmount) -> ContractMethod) -> EventCmd {
+	fn new(event_name: &str, method: EventMethod) -> EventCmd {
+		let mut inputs = vec![
+			EventParam {
+				name: "sender".into(),
+				kind: ParamType::Address,
+				indexed: true,
+			},
+		];
+
+		if !matches!(method, EventMethod::Eth(_)) {
+			inputs.push(EventParam {
+				name: "token".into(),
+				kind: ParamType::Address,
+				indexed: true,
+			});
+		}
+
+		let values: &[&str] = match method {
+			EventMethod::Swap(_) => &["amount", "limit", "deadline"],
+			_ => &["value"],
+		};
+
+		for value in values {
+			inputs.push(EventParam {
+				name: (*value).into(),
+				kind: ParamType::Uint(256),
+				indexed: false,
+			});
+		}
+
 		let event = Event {
 			name: event_name.into(),
-			inputs: vec![
-				EventParam {
-					name: "sender".into(),
-					kind: ParamType::Address,
-					indexed: true,
-				},
-				EventParam {
-					name: "value".into(),
-					kind: ParamType::Uint(256),
-					indexed: false,
-				},
-			],
+			inputs,
 			anonymous: false,
 		};
 
@@ -66,8 +112,17 @@ impl EventCmd {
 	fn parse(&self, raw_log: RawLog) -> Option<ContractMethod> {
 		match self.event.parse_log(raw_log) {
 			Ok(log) => {
-				if let Ok(sa) = SenderAmount::try_from(log) {
-					Some((self.method)(sa))
+				let result = match self.method {
+					EventMethod::Eth(method) => SenderAmount::try_from(log)
+						.map(|sa| method(sa)),
+					EventMethod::Token(method) => SenderAmount::try_from_token_log(log)
+						.map(|(token, sa)| method(token, sa)),
+					EventMethod::Swap(method) => SwapOrder::try_from_token_log(log)
+						.map(|(token, so)| method(token, so)),
+				};
+
+				if let Ok(cmd) = result {
+					Some(cmd)
 				} else {
 					debug::error!("parse_sender_value_event");
 					None
@@ -81,9 +136,9 @@ impl EventCmd {
 	}
 }
 
-fn get_vault_topic_hash(event_name: &str) -> Hash {
+fn get_vault_topic_hash(event_name: &str, method: &EventMethod) -> Hash {
 	let mut topic: Vec<u8> = event_name.into();
-	let mut params: Vec<u8> = b"(address,uint256)".to_vec();
+	let mut params: Vec<u8> = method.params().to_vec();
 	topic.append(&mut params);
 	get_topic_hash(&topic)
 }
@@ -0,0 +1,62 @@
+//! Signing of Ethereum messages with secp256k1 keys from node keystore.
+//!
+//! `sp_io::crypto::ecdsa_sign` hashes message with blake2 before signing, while vault contract
+//! recovers signer from keccak hash. So the node provides host function which signs
+//! 32 bytes digest as is, see `EthSignerExt`.
+
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use sp_core::{crypto::KeyTypeId, ecdsa};
+use sp_runtime_interface::runtime_interface;
+#[cfg(feature = "std")]
+use sp_externalities::ExternalitiesExt;
+
+use crate::entities::EthAddress;
+use crate::eth_bridge::merkle::keccak_256;
+
+/// Signer of prehashed messages, implemented by the node on top of its keystore
+#[cfg(feature = "std")]
+pub trait PrehashedSigner: Send + Sync {
+	fn sign_prehashed(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		digest: &[u8; 32],
+	) -> Option<ecdsa::Signature>;
+}
+
+#[cfg(feature = "std")]
+sp_externalities::decl_extension! {
+	/// Externalities extension which gives offchain worker access to `PrehashedSigner`
+	pub struct EthSignerExt(Arc<dyn PrehashedSigner>);
+}
+
+#[runtime_interface]
+pub trait EthSigner {
+	/// Signs digest with ecdsa key of provided type, None if key or extension is not available
+	fn sign_prehashed(
+		&mut self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		digest: &[u8; 32],
+	) -> Option<ecdsa::Signature> {
+		self.extension::<EthSignerExt>()
+			.and_then(|ext| ext.0.sign_prehashed(key_type, public, digest))
+	}
+}
+
+/// Ethereum address of secp256k1 public key: last 20 bytes of keccak256 of uncompressed key
+pub fn eth_address(public: &ecdsa::Public) -> Option<EthAddress> {
+	let mut compressed = [0u8; 33];
+	compressed.copy_from_slice(public.as_ref());
+
+	let uncompressed = secp256k1::PublicKey::parse_compressed(&compressed).ok()?.serialize();
+	Some(eth_address_from_raw(&uncompressed[1..]))
+}
+
+/// Ethereum address of raw 64 bytes public key, as returned by `secp256k1_ecdsa_recover`
+pub fn eth_address_from_raw(public: &[u8]) -> EthAddress {
+	let hash = keccak_256(public);
+	ethabi::Address::from_slice(&hash.as_bytes()[12..]).into()
+}
@@ -1,18 +1,18 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use core::{cmp, convert::*};
+use core::convert::*;
 
-use codec::Encode;
-use frame_support::{debug, decl_error, decl_event, decl_module, decl_storage,
-					dispatch::{DispatchError, DispatchResult}, traits::Get};
+use codec::{Decode, Encode};
+use frame_support::{debug, decl_error, decl_event, decl_module, decl_storage, ensure,
+					dispatch::DispatchResult, traits::Get, weights::Weight,
+					storage::{with_transaction, TransactionOutcome}};
 use frame_system::{
-	self as system, ensure_signed,
+	self as system, ensure_root, ensure_signed,
 	offchain::{
 		AppCrypto, CreateSignedTransaction,
 	},
 };
-use sp_core::crypto::KeyTypeId;
-use sp_runtime::traits::IntegerSquareRoot;
+use sp_core::{crypto::KeyTypeId, ecdsa, H256};
 use sp_std::{
 	prelude::*, str,
 };
@@ -22,12 +22,26 @@ use sp_std::collections::btree_map::BTreeMap;
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
 use sp_std::convert::TryFrom;
 
-use crate::entities::{BlockEvents, ContractMethod::*, EthAddress, SenderAmount, Uint256};
+use crate::entities::{BlockEvents, ContractMethod, ContractMethodKind, EthAddress, Hash256, LogId, Price, SyncStatus, Uint256, Withdrawal};
 
+mod amm;
+mod attestation;
+mod contract;
 mod offchain;
-mod entities;
+mod pool;
+mod processed_logs;
+mod reorg;
+mod withdrawal;
+pub mod entities;
 mod errors;
 mod eth_bridge;
+pub mod eth_signer;
+pub mod runtime_api;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 
 /// Defines application identifier for crypto keys of this module.
 ///
@@ -38,28 +52,27 @@ mod eth_bridge;
 /// The keys can be inserted manually via RPC (see `author_insertKey`).
 pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"plsw");
 
-pub const FETCH_TIMEOUT_PERIOD: u64 = 3000;
-
-// in milli-seconds
-pub const LOCK_TIMEOUT_EXPIRATION: u64 = FETCH_TIMEOUT_PERIOD + 1000;
+/// Identifier for secp256k1 keys which validators use to sign withdrawals for vault contract
+pub const ETH_KEY_TYPE: KeyTypeId = KeyTypeId(*b"plse");
 
-// in milli-seconds
-pub const LOCK_BLOCK_EXPIRATION: u32 = 3; // in block number
+/// Timeout of JSON RPC request to ethereum provider, in milli-seconds
+pub const FETCH_TIMEOUT_PERIOD: u64 = 30000;
 
-/// Vault contract address
-pub const VAULT_CONTRACT_ADDRESS: &'static str = "138DbEB43704dBC1EB8BF3108191480488fcD6Be";
+/// JSON RPC requests of eth sync run besides block headers: last block, vault logs and token logs
+pub const MAX_ETH_SYNC_REQUESTS: u64 = 3;
 
-/// Token contract agaist Eth erc20 contract address
-/// DAI on our case
-pub const TOKEN_CONTRACT_ADDRESS: &'static str = "ad6d458402f60fd3bd25163575031acdce07538d";
+/// Time of eth sync run besides JSON RPC requests, in milli-seconds
+pub const LOCK_TIMEOUT_EXPIRATION: u64 = 1000;
 
-pub const TOKEN_DECIMALS :u32 = 6;
+/// Maximum number of ethereum blocks which could be synced by one extrinsic
+pub const MAX_ETH_SYNC_BATCH_SIZE: u32 = 100;
 
-/// Initial ratio for token to ETH
-/// Used at first withdraw
-pub const INITIAL_RATIO: u128 = 1000u128;
+/// Offchain eth sync lock expires after both block and time deadlines
+pub const LOCK_BLOCK_EXPIRATION: u32 = 3; // in block number
 
-pub const MINIMAL_LIQUIDITY: u128 = 1000u128;
+/// Key for persistent offchain storage with SCALE encoded `Vec<Vec<u8>>` of ethereum JSON RPC urls,
+/// node writes them at start, offchain workers fall back to `Trait::EthProviderEndpoint` if there are none
+pub const LS_ETH_PROVIDER_URLS_KEY: &[u8] = b"offchain-polkaswap::eth_provider_urls";
 
 /// Based on the above `KeyTypeId` we need to generate a pallet-specific crypto type wrapper.
 /// We can utilize the supported crypto kinds (`sr25519`, `ed25519` and `ecdsa`) and augment
@@ -96,6 +109,15 @@ pub mod crypto {
 }
 
 
+/// Secp256k1 keys of validators, signatures are verified by vault contract with `ecrecover`
+pub mod eth_crypto {
+	use sp_runtime::app_crypto::{app_crypto, ecdsa};
+
+	use crate::ETH_KEY_TYPE;
+
+	app_crypto!(ecdsa, ETH_KEY_TYPE);
+}
+
 /// This is the pallet's configuration trait
 pub trait Trait: system::Trait + CreateSignedTransaction<Call<Self>> {
 	/// The identifier type for an offchain worker.
@@ -105,6 +127,7 @@ pub trait Trait: system::Trait + CreateSignedTransaction<Call<Self>> {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
+	/// Default ethereum JSON RPC endpoint, used if node isn't started with `--eth-rpc-url`
 	type EthProviderEndpoint: Get<&'static str>;
 }
 
@@ -118,24 +141,113 @@ decl_storage! {
 	 	/// Last block synced with ethereum
         pub EthLastSyncedBlock get(fn eth_last_synced_block): u32;
 
-        /// Token balance for eth user
-        pub TokenBalance get(fn token_balance): map hasher(blake2_128_concat) EthAddress => Uint256;
+        /// Pool registry: token contract address => token decimals
+        /// Each registered ERC20 token is paired with ETH in its own pool
+        pub Tokens get(fn token_decimals) config(): map hasher(blake2_128_concat) EthAddress => Option<u32>;
+
+        /// Token balance for eth user: (user, token) => balance
+        pub TokenBalance get(fn token_balance): double_map hasher(blake2_128_concat) EthAddress, hasher(blake2_128_concat) EthAddress => Uint256;
 
         /// Eth balance for eth user
         pub EthBalance get(fn eth_balance): map hasher(blake2_128_concat) EthAddress => Uint256;
 
-         /// Liquidity balance for eth user
-        pub LiquidityBalance get(fn liquidity_balance): map hasher(blake2_128_concat) EthAddress => Uint256;
+         /// Liquidity balance for eth user: (user, token) => balance
+        pub LiquidityBalance get(fn liquidity_balance): double_map hasher(blake2_128_concat) EthAddress, hasher(blake2_128_concat) EthAddress => Uint256;
 
         /// Pool token liquidity
-        pub PoolTokenLiquidity get(fn pool_token_liquidity) : Uint256;
+        pub PoolTokenLiquidity get(fn pool_token_liquidity): map hasher(blake2_128_concat) EthAddress => Uint256;
 
 		/// Pool eth liquidity
-        pub PoolETHLiquidity get(fn pool_eth_liquidity) : Uint256;
+        pub PoolETHLiquidity get(fn pool_eth_liquidity): map hasher(blake2_128_concat) EthAddress => Uint256;
+
+        /// Total supply for liquidity tokens of pool
+        pub TotalSupply get(fn total_supply): map hasher(blake2_128_concat) EthAddress => Uint256;
+
+        /// Vault contract address
+        pub VaultContractAddress get(fn vault_contract_address) config(): EthAddress;
+
+        /// Initial ratio for token to ETH: whole tokens per 1 ETH, scaled by decimals of each token
+        /// Used at first add liquidity
+        pub InitialRatio get(fn initial_ratio) config(): u128;
+
+        /// Liquidity which is locked forever at first add liquidity
+        pub MinimalLiquidity get(fn minimal_liquidity) config(): u128;
+
+        /// Number of block height (confirmations) to be synced with polkaswap
+        pub EthConfirmationDepth get(fn eth_confirmation_depth) config(): u32;
+
+        /// Ethereum block to start sync from, usually vault contract deployment block
+        /// It's mandatory, otherwise vault events before the first synced block would be missed
+        pub EthStartBlock get(fn eth_start_block) config(): u32;
+
+        /// Number of ethereum blocks which offchain worker fetches and submits at once
+        pub EthSyncBatchSize get(fn eth_sync_batch_size) config(): u32;
+
+        /// Hashes of synced ethereum blocks, used to check that next block is linked to them
+        pub EthBlockHashes get(fn eth_block_hash): map hasher(twox_64_concat) u32 => Option<Hash256>;
+
+        /// Sync is halted when submitted block is not linked to the last synced block
+        pub EthSyncHalted get(fn eth_sync_halted): bool;
 
-        /// Total supply for liquidity tokens
-        pub TotalSupply get(fn total_supply) : Uint256;
+        /// Ethereum logs which contract methods were applied: log id => eth block number
+        /// The same log is never applied twice while it's kept
+        pub ProcessedLogs get(fn processed_log): map hasher(blake2_128_concat) LogId => Option<u32>;
 
+        /// Processed logs of ethereum block, used to prune them
+        pub BlockProcessedLogs get(fn block_processed_logs): map hasher(twox_64_concat) u32 => Vec<LogId>;
+
+        /// Number of ethereum blocks which processed logs are kept, zero keeps them forever
+        pub ProcessedLogsDepth get(fn processed_logs_depth) config(): u32;
+
+        /// Last ethereum block which processed logs are pruned
+        pub ProcessedLogsPrunedBlock get(fn processed_logs_pruned_block): u32;
+
+        /// Accounts which are authorized to submit ethereum blocks
+        pub Relayers get(fn relayers) config(): Vec<T::AccountId>;
+
+        /// Number of relayers which should submit identical block events to apply the block
+        /// It's between 1 and number of relayers
+        pub RelayerThreshold get(fn relayer_threshold) config(): u32;
+
+        /// Relayers which voted for block: (block number, hash of block events) => relayers
+        pub BlockVotes get(fn block_votes): double_map hasher(twox_64_concat) u32, hasher(identity) H256 => Vec<T::AccountId>;
+
+        /// Hash of block events which relayer voted for: (block number, relayer) => hash
+        pub RelayerVotes get(fn relayer_votes): double_map hasher(twox_64_concat) u32, hasher(blake2_128_concat) T::AccountId => Option<H256>;
+
+        /// Nonce of the next withdrawal
+        pub WithdrawalNonce get(fn withdrawal_nonce): u64;
+
+        /// Withdrawals queue: nonce => withdrawal
+        pub Withdrawals get(fn withdrawals): map hasher(twox_64_concat) u64 => Option<Withdrawal>;
+
+        /// Nonces of withdrawals queued in current block, sealed at the end of the block
+        pub PendingWithdrawals get(fn pending_withdrawals): Vec<u64>;
+
+        /// Nonces of withdrawals sealed in L2 block
+        pub BlockWithdrawals get(fn block_withdrawals): map hasher(twox_64_concat) T::BlockNumber => Vec<u64>;
+
+        /// L2 block where withdrawal was sealed: nonce => block number
+        pub WithdrawalBlock get(fn withdrawal_block): map hasher(twox_64_concat) u64 => Option<T::BlockNumber>;
+
+        /// Merkle root of withdrawals sealed in L2 block, used by vault contract to verify claims
+        pub WithdrawalRoots get(fn withdrawal_root): map hasher(twox_64_concat) T::BlockNumber => Option<H256>;
+
+        /// Ethereum addresses of validators which sign withdrawals
+        pub EthSigners get(fn eth_signers) config(): Vec<EthAddress>;
+
+        /// Validators signatures of withdrawal: nonce => (signer, signature)
+        pub WithdrawalSignatures get(fn withdrawal_signatures): map hasher(twox_64_concat) u64 => Vec<(EthAddress, ecdsa::Signature)>;
+
+    }
+    add_extra_genesis {
+		build(|config: &GenesisConfig<T>| {
+			assert!(
+				config.relayer_threshold >= 1 && config.relayer_threshold as usize <= config.relayers.len(),
+				"Relayer threshold should be between 1 and number of relayers"
+			);
+			assert!(config.eth_start_block > 0, "Eth start block should be set");
+		});
     }
 }
 
@@ -144,22 +256,55 @@ decl_storage! {
 decl_event!(
 	 pub enum Event<T> where
         AccountId = <T as frame_system::Trait>::AccountId,
+        BlockNumber = <T as frame_system::Trait>::BlockNumber,
     {
     	// emitted when user deposit tokens on account
-        DepositedToken(Vec<u8>, u128),
-        DepositedETH(Vec<u8>, u128),
-        WithdrawToken(Vec<u8>, u128),
-        WithdrawETH(Vec<u8>, u128),
-		SwapToToken(Vec<u8>, u128),
-		SwapToETH(Vec<u8>, u128),
-		AddLiquidity(Vec<u8>, u128),
-		RemoveLiquidity(Vec<u8>, u128),
-
+    	// token events are (eth log id, sender, token, amount)
+        DepositedToken(LogId, Vec<u8>, Vec<u8>, u128),
+        DepositedETH(LogId, Vec<u8>, u128),
+        WithdrawToken(LogId, Vec<u8>, Vec<u8>, u128),
+        WithdrawETH(LogId, Vec<u8>, u128),
+		SwapToToken(LogId, Vec<u8>, Vec<u8>, u128),
+		SwapToETH(LogId, Vec<u8>, Vec<u8>, u128),
+		AddLiquidity(LogId, Vec<u8>, Vec<u8>, u128),
+		RemoveLiquidity(LogId, Vec<u8>, Vec<u8>, u128),
+
+		// emitted when block reached relayer threshold and was applied
 		EthBlockSynced(u32),
 		ValueSet(AccountId, u32),
 
-		// Errors
-		ContractError(Vec<u8>),
+		// Relayer attestation: (relayer, block number, hash of block events)
+		EthBlockVoted(AccountId, u32, H256),
+		// Relayers submitted different events for the same block: (block number, voted hash, new hash)
+		EthBlockVoteConflict(u32, H256, H256),
+		// Block is not linked to the last synced block, sync is halted:
+		// (block number, hash of last synced block, parent hash of the block)
+		EthReorgDetected(u32, Hash256, Hash256),
+		// Sync is resumed from the block
+		EthSyncRolledBack(u32),
+
+		// Bridge configuration updates
+		VaultContractAddressUpdated(EthAddress),
+		TokenRegistered(EthAddress, u32),
+		// Pool of token is moved to new token contract address: (token, new address)
+		TokenContractAddressUpdated(EthAddress, EthAddress),
+		EthConfirmationDepthUpdated(u32),
+		EthStartBlockUpdated(u32),
+		EthSyncBatchSizeUpdated(u32),
+		ProcessedLogsDepthUpdated(u32),
+		RelayerAdded(AccountId),
+		RelayerRemoved(AccountId),
+		RelayerThresholdUpdated(u32),
+
+		// Withdrawals sealed in L2 block: (block number, merkle root, nonces)
+		WithdrawalsQueued(BlockNumber, H256, Vec<u64>),
+		// Validator signed withdrawal: (nonce, signer)
+		WithdrawalSigned(u64, EthAddress),
+		EthSignersUpdated(Vec<EthAddress>),
+
+		// Contract method was rejected and its changes were reverted, e.g. swap bound is violated:
+		// (eth block number, index of method in block, eth log id, sender, method, reason)
+		MethodFailed(u32, u32, LogId, EthAddress, ContractMethodKind, ContractError),
 }
 );
 
@@ -184,20 +329,81 @@ decl_error! {
 		EventParsingError,
 
 		ContractTokenError,
+
+		// Errors returned when pool registry is updated
+		TokenNotRegistered,
+		TokenAlreadyRegistered,
+		// Error returned when there are more balances than bound of token contract address update
+		TooManyBalances,
+
+		// Error returned when ethereum block is submitted by account which is not a relayer
+		NotAuthorizedRelayer,
+		RelayerAlreadyExists,
+		RelayerNotFound,
+
+		// Error returned when relayer already submitted the block
+		AlreadyVoted,
+		// Error returned when threshold is zero or exceeds number of relayers
+		InvalidThreshold,
+
+		// Errors returned when ethereum chain reorganization is detected
+		EthBlockNotFound,
+		EthBlockHashMismatch,
+		EthSyncHalted,
+		InvalidRollbackBlock,
+		// Error returned when rolled back blocks have processed logs, their state changes can't be reverted
+		RollbackOverProcessedLogs,
+
+		// Error returned when batch is empty, too big or contains not consecutive blocks
+		InvalidBatch,
+
+		// Error returned when block is already synced or it's not the next block to sync
+		UnexpectedEthBlock,
+		// Errors returned when start block is zero or it's not set in storage
+		InvalidStartBlock,
+		EthStartBlockNotSet,
+		// Error returned when start block is updated after blocks were synced
+		EthSyncStarted,
+
+		// Errors returned when withdrawal signature is submitted
+		UnknownWithdrawal,
+		InvalidWithdrawalSignature,
+		UnknownEthSigner,
 	}
 }
 
-pub struct ContractError(&'static str);
+/// Reason why contract method was rejected, it's emitted in MethodFailed event
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Copy, Clone)]
+pub enum ContractError {
+	// User has no balance of the asset
+	UnknownUser,
+	TokenNotRegistered,
+	// User balance is empty, there is nothing to withdraw
+	NothingToWithdraw,
+	ZeroAmount,
+	InsufficientLiquidity,
+	InsufficientEthBalance,
+	InsufficientTokenBalance,
+	// Swap slippage limits: output is below minimum or input is above maximum
+	InsufficientOutputAmount,
+	ExcessiveInputAmount,
+	DeadlineExpired,
+	Overflow,
+	Underflow,
+	DivisionByZero,
+}
 
+/// Result of applied contract method: (sender, token, amount)
+#[derive(Debug, Encode, Decode, Eq, PartialEq, Clone)]
 pub enum ContractEvent {
-	DepositedToken(Vec<u8>, u128),
+	DepositedToken(Vec<u8>, Vec<u8>, u128),
 	DepositedETH(Vec<u8>, u128),
-	WithdrawToken(Vec<u8>, u128),
+	WithdrawToken(Vec<u8>, Vec<u8>, u128),
 	WithdrawETH(Vec<u8>, u128),
-	SwapToToken(Vec<u8>, u128),
-	SwapToETH(Vec<u8>, u128),
-	AddLiquidity(Vec<u8>, u128),
-	RemoveLiquidity(Vec<u8>, u128),
+	SwapToToken(Vec<u8>, Vec<u8>, u128),
+	SwapToETH(Vec<u8>, Vec<u8>, u128),
+	AddLiquidity(Vec<u8>, Vec<u8>, u128),
+	RemoveLiquidity(Vec<u8>, Vec<u8>, u128),
 }
 
 // Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -212,314 +418,356 @@ decl_module! {
 		/// It gets Block events entities and update state based on method it contains
 		/// After updating state, it updates EthLastSyncedBlock, writing the last block number
 		/// @returns DispatchResult
-        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        #[weight = Module::<T>::sync_weight(sp_std::slice::from_ref(be))]
         pub fn sync_eth_block(origin, be: BlockEvents) -> DispatchResult  {
+        	let who = ensure_signed(origin)?;
+        	ensure!(Self::relayers().contains(&who), Error::<T>::NotAuthorizedRelayer);
         	debug::info!("{:?}", be);
 
-			///
-			/// ==================== ::CONTRACT FUNCTIONS:: ==========================
-			/// Deposit token for user
-        	fn deposit_token(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-				debug::info!("deposit_token: {:?}", sa);
-				let updated_balance = if TokenBalance::contains_key(&sa.sender) {
-						sa.amount + TokenBalance::get(&sa.sender)
-					} else { sa.amount };
-
-				TokenBalance::insert(&sa.sender, &updated_balance);
-				Ok(ContractEvent::DepositedToken(sa.sender.encode(), updated_balance.into()).into())
-
-        	}
-
-			/// Deposit Eth for user
-			fn deposit_eth(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-				debug::info!("deposit_eth: {:?}", sa);
-				let updated_balance = if EthBalance::contains_key(&sa.sender) {
-									sa.amount + EthBalance::get(&sa.sender)
-								} else { sa.amount};
-
-				EthBalance::insert(&sa.sender, &updated_balance);
-				Ok(ContractEvent::DepositedETH(sa.sender.encode(), updated_balance.into()))
-			}
-
-			/// Withdraw function
-			/// @return SenderAmount with real numbers to be withdrawn, else ContractError
-			fn withdraw_token(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-				if TokenBalance::contains_key(&sa.sender) {
-					return Err(ContractError("User not found"));
-				}
-				let amount = get_min_user_token_balance(&sa);
-				if amount.clone() > Uint256::from(0)  {
-					let updated_balance = TokenBalance::get(&sa.sender) - amount;
-					TokenBalance::insert(&sa.sender, &updated_balance);
-					Ok(ContractEvent::WithdrawToken(sa.sender.encode(), updated_balance.into()))
-				} else {
-					Err(ContractError("Nothing to with"))
-				}
-			}
-
-			/// Withdraw function
-			/// @return SenderAmount with real numbers to be withdrawn, else ContractError
-			fn withdraw_eth(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-				if EthBalance::contains_key(&sa.sender) {
-					return Err(ContractError("User not found"));
-				}
-				let amount = get_min_user_eth_balance(&sa);
-				if amount.clone() > Uint256::from(0)  {
-					let updated_balance = EthBalance::get(&sa.sender) - amount;
-					EthBalance::insert(&sa.sender, &updated_balance);
-					Ok(ContractEvent::WithdrawETH(sa.sender.encode(), updated_balance.into()))
-				} else {
-					Err(ContractError("Nothing to with"))
-				}
-			}
+        	Self::sync_blocks(&who, vec![be])
+        }
 
-			/// SwapToToken
-			fn swap_to_token(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-				debug::info!("swap_to_token");
-				let desired_token_amount = sa.amount;
-				let pool_token_liquidity = PoolTokenLiquidity::get();
-				let pool_eth_liquidity = PoolETHLiquidity::get();
+		/// SYNC BATCH OF ETH_BLOCKS EVENTS
+		/// Blocks should be consecutive, starting from the next block to sync
+		/// Each block is voted separately and applied in order once it reached relayer threshold
+        #[weight = Module::<T>::sync_weight(blocks)]
+        pub fn sync_eth_block_batch(origin, blocks: Vec<BlockEvents>) -> DispatchResult  {
+        	let who = ensure_signed(origin)?;
+        	ensure!(Self::relayers().contains(&who), Error::<T>::NotAuthorizedRelayer);
+        	debug::info!("Batch of {} eth blocks", blocks.len());
 
-				if desired_token_amount > pool_token_liquidity {
-					return Err(ContractError("Not enough token liquidity"));
-				}
-
-				let amount_eth_to_withdraw = desired_token_amount / get_ratio() * Uint256::from(1000) / Uint256::from(997);
-				let amount_eth_user = EthBalance::get(&sa.sender);
+        	Self::sync_blocks(&who, blocks)
+        }
 
-				if amount_eth_to_withdraw > amount_eth_user {
-					return Err(ContractError("Not enough eth on user account"));
-				}
+        /// Rolls ethereum sync back to the block and resumes halted sync, could be called by root only
+        /// State changes of rolled back blocks are not reverted, so only blocks without processed logs
+        /// could be rolled back, otherwise logs re-mined into other blocks would be applied twice.
+        /// Sync halted on reorg of blocks with processed logs stays halted till state is fixed by runtime upgrade
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(
+        	reorg::ETH_BLOCK_HASHES_DEPTH as Weight + 3,
+        	(reorg::ETH_BLOCK_HASHES_DEPTH + 2 * MAX_ETH_SYNC_BATCH_SIZE) as Weight + 2,
+        )]
+        pub fn rollback_eth_sync(origin, block_number: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	Self::rollback_to(block_number)?;
+        	Self::deposit_event(RawEvent::EthSyncRolledBack(block_number));
+        	Ok(())
+        }
 
-				let amount_token_user = TokenBalance::get(&sa.sender);
+        /// Updates vault contract address, could be called by root only
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn set_vault_contract_address(origin, address: EthAddress) -> DispatchResult {
+        	ensure_root(origin)?;
+        	VaultContractAddress::put(address);
+        	Self::deposit_event(RawEvent::VaultContractAddressUpdated(address));
+        	Ok(())
+        }
 
-				let updated_user_eth_balance = amount_eth_user - amount_eth_to_withdraw;
-				let updated_user_token_balance = amount_token_user + desired_token_amount;
+        /// Registers ERC20 token and creates ETH/token pool for it, could be called by root only
+        /// Calling it for registered token updates its decimals
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn register_token(origin, token: EthAddress, decimals: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	Tokens::insert(&token, decimals);
+        	Self::deposit_event(RawEvent::TokenRegistered(token, decimals));
+        	Ok(())
+        }
 
-				PoolTokenLiquidity::set(pool_token_liquidity - desired_token_amount);
-				PoolETHLiquidity::set(pool_eth_liquidity + amount_eth_to_withdraw);
+        /// Moves pool of registered token to new token contract address, could be called by root only
+        /// It's used when token contract is migrated on Ethereum, balances of all users are moved too
+        /// max_balances bounds number of token and liquidity balances of all tokens, which are iterated
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(
+        	*max_balances as Weight + 6,
+        	2 * *max_balances as Weight + 8,
+        )]
+        pub fn set_token_contract_address(origin, token: EthAddress, address: EthAddress, max_balances: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	ensure!(Tokens::contains_key(&token), Error::<T>::TokenNotRegistered);
+        	ensure!(!Tokens::contains_key(&address), Error::<T>::TokenAlreadyRegistered);
+        	Self::move_pool(&token, &address, max_balances)?;
+        	Self::deposit_event(RawEvent::TokenContractAddressUpdated(token, address));
+        	Ok(())
+        }
 
-				TokenBalance::insert(&sa.sender, &updated_user_token_balance);
-				EthBalance::insert(&sa.sender, &updated_user_eth_balance);
+        /// Updates number of ethereum confirmations, could be called by root only
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn set_eth_confirmation_depth(origin, depth: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	EthConfirmationDepth::put(depth);
+        	Self::deposit_event(RawEvent::EthConfirmationDepthUpdated(depth));
+        	Ok(())
+        }
 
-				Ok(ContractEvent::SwapToToken(sa.sender.encode(), updated_user_token_balance.into()))
+        /// Updates ethereum block to start sync from, could be called by root only
+        /// It's used only while nothing is synced, e.g. after sync is rolled back to zero block
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn set_eth_start_block(origin, block_number: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	ensure!(block_number > 0, Error::<T>::InvalidStartBlock);
+        	ensure!(EthLastSyncedBlock::get() == 0, Error::<T>::EthSyncStarted);
+        	EthStartBlock::put(block_number);
+        	Self::deposit_event(RawEvent::EthStartBlockUpdated(block_number));
+        	Ok(())
+        }
 
-			}
+        /// Updates number of ethereum blocks synced at once, could be called by root only
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn set_eth_sync_batch_size(origin, batch_size: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	ensure!(batch_size > 0 && batch_size <= MAX_ETH_SYNC_BATCH_SIZE, Error::<T>::InvalidBatch);
+        	EthSyncBatchSize::put(batch_size);
+        	Self::deposit_event(RawEvent::EthSyncBatchSizeUpdated(batch_size));
+        	Ok(())
+        }
 
-			/// SwapToToken
-			fn swap_to_eth(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-				debug::info!("swap_to_eth");
-				let desired_eth_amount = sa.amount;
-				let pool_token_liquidity = PoolTokenLiquidity::get();
-				let pool_eth_liquidity = PoolETHLiquidity::get();
+        /// Updates number of ethereum blocks which processed logs are kept, could be called by root only
+        /// Depth should exceed the deepest block which could be submitted again
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn set_processed_logs_depth(origin, depth: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	ProcessedLogsDepth::put(depth);
+        	Self::deposit_event(RawEvent::ProcessedLogsDepthUpdated(depth));
+        	Ok(())
+        }
 
-				if desired_eth_amount > pool_eth_liquidity {
-					return Err(ContractError("Not enough eth liquidity"));
-				}
+        /// Authorizes account to submit ethereum blocks, could be called by root only
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn add_relayer(origin, relayer: T::AccountId) -> DispatchResult {
+        	ensure_root(origin)?;
+        	let mut relayers = Self::relayers();
+        	ensure!(!relayers.contains(&relayer), Error::<T>::RelayerAlreadyExists);
+        	relayers.push(relayer.clone());
+        	Relayers::<T>::put(relayers);
+        	Self::deposit_event(RawEvent::RelayerAdded(relayer));
+        	Ok(())
+        }
 
-				let amount_token_to_withdraw = desired_eth_amount * get_ratio() * Uint256::from(1000) / Uint256::from(997);
-				let amount_token_user = TokenBalance::get(&sa.sender);
+        /// Revokes relayer authorization, could be called by root only
+        /// Its votes for blocks which are not applied yet are not counted anymore
+        /// Threshold should be decreased first if relayers would not be enough to reach it
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn remove_relayer(origin, relayer: T::AccountId) -> DispatchResult {
+        	ensure_root(origin)?;
+        	let mut relayers = Self::relayers();
+        	ensure!(relayers.contains(&relayer), Error::<T>::RelayerNotFound);
+        	relayers.retain(|r| r != &relayer);
+        	ensure!(Self::relayer_threshold() as usize <= relayers.len(), Error::<T>::InvalidThreshold);
+        	Relayers::<T>::put(relayers);
+        	Self::deposit_event(RawEvent::RelayerRemoved(relayer));
+        	Ok(())
+        }
 
-				if amount_token_to_withdraw > amount_token_user {
-					return Err(ContractError("Not enough eth on user account"));
-				}
+        /// Updates number of relayers which should attest block, could be called by root only
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn set_relayer_threshold(origin, threshold: u32) -> DispatchResult {
+        	ensure_root(origin)?;
+        	ensure!(threshold >= 1 && threshold as usize <= Self::relayers().len(), Error::<T>::InvalidThreshold);
+        	RelayerThreshold::put(threshold);
+        	Self::deposit_event(RawEvent::RelayerThresholdUpdated(threshold));
+        	Ok(())
+        }
 
-				let amount_eth_user = EthBalance::get(&sa.sender);
+        /// Submits validators signatures of withdrawals: (nonce, signature)
+        /// Signer is recovered from signature and should be in EthSigners list
+        #[weight = 10_000 + T::DbWeight::get().writes(signatures.len() as u64)]
+        pub fn submit_withdrawal_signatures(origin, signatures: Vec<(u64, ecdsa::Signature)>) -> DispatchResult {
+        	ensure_signed(origin)?;
+
+        	// All signatures are verified before storing any of them
+        	let mut verified: Vec<(u64, EthAddress, ecdsa::Signature)> = vec![];
+        	for (nonce, signature) in signatures {
+        		let signer = Self::recover_withdrawal_signer(nonce, &signature)?;
+        		verified.push((nonce, signer, signature));
+        	}
 
-				let updated_user_eth_balance = amount_eth_user + desired_eth_amount;
-				let updated_user_token_balance = amount_token_user - amount_token_to_withdraw;
+        	for (nonce, signer, signature) in verified {
+        		let mut stored = WithdrawalSignatures::get(nonce);
+        		if stored.iter().any(|(s, _)| s == &signer) {
+        			continue;
+        		}
+        		stored.push((signer, signature));
+        		WithdrawalSignatures::insert(nonce, stored);
+        		Self::deposit_event(RawEvent::WithdrawalSigned(nonce, signer));
+        	}
+        	Ok(())
+        }
 
-				PoolTokenLiquidity::set(pool_token_liquidity + amount_token_to_withdraw);
-				PoolETHLiquidity::set(pool_eth_liquidity - desired_eth_amount);
+        /// Updates ethereum addresses of validators which sign withdrawals, could be called by root only
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn set_eth_signers(origin, signers: Vec<EthAddress>) -> DispatchResult {
+        	ensure_root(origin)?;
+        	EthSigners::put(signers.clone());
+        	Self::deposit_event(RawEvent::EthSignersUpdated(signers));
+        	Ok(())
+        }
 
-				TokenBalance::insert(&sa.sender, &updated_user_token_balance);
-				EthBalance::insert(&sa.sender, &updated_user_eth_balance);
-				Ok(ContractEvent::SwapToETH(sa.sender.encode(), updated_user_eth_balance.into()))
+        // Withdrawals queued in the block are sealed in merkle tree
+        fn on_finalize(block_number: T::BlockNumber) {
+        	Self::seal_withdrawals(block_number);
+        }
 
+        // Offchain worker runs after each block
+		fn offchain_worker(_block_number: T::BlockNumber) {
+			Self::offchain_eth_sync();
+			if let Err(e) = Self::offchain_sign_withdrawals() {
+				debug::error!("Withdrawals signing failed: {:?}", e);
 			}
-
-			/// AddLiquidity function
-			/// Amount means value in eth
-			/// @return SenderAmount with real numbers to be withdrawn, else ContractError
-			fn add_liquidity(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-
-				debug::info!("add_liquidity");
-				debug::info!("Got eth amount: {:}", &sa.amount);
-
-				let total_supply = TotalSupply::get();
-				debug::info!("total_supply: {:}", &total_supply);
-
-				let mut amount_eth = get_min_user_eth_balance(&sa);
-				debug::info!("amount eth: {:}", &amount_eth);
-
-				let amount_token = amount_eth * get_ratio();
-				debug::info!("amount token: {:}", &amount_token);
-
-				let user_token_balance = TokenBalance::get(&sa.sender);
-				let amount_token_upd = cmp::min(amount_token, user_token_balance);
-
-				if amount_token_upd < amount_token {
-					debug::info!("EBAT, IF");
-					amount_eth = amount_token_upd / get_ratio();
-					debug::info!("amount_eth: {:?}", amount_eth);
-					debug::info!("amount_token_upd: {:?}", amount_token);
-				}
-				let amount_token = amount_token_upd;
-
-				debug::info!("amount_eth: {:?}", amount_eth);
-				debug::info!("amount_token_upd: {:?}", amount_token);
-
-				let mut liquidity : Uint256;
-				let pool_token_liquidity = PoolTokenLiquidity::get();
-				let pool_eth_liquidity = PoolETHLiquidity::get();
-
-				if total_supply == Uint256::from(0) {
-					let initial_value = u128::from(amount_eth).integer_sqrt() * u128::from(amount_token).integer_sqrt();
-
-					if initial_value < MINIMAL_LIQUIDITY {
-						return Err(ContractError("Not enough liquidity"));
-					}
-					liquidity = (initial_value - MINIMAL_LIQUIDITY).into();
-
-					// LiquidityBalance::insert(EthAddre, &updated_balance);
-				} else {
-
-					liquidity = cmp::min(amount_eth * total_supply / pool_eth_liquidity, amount_token * total_supply/pool_token_liquidity);
-				}
-
-				let mut user_liquidity_balance = LiquidityBalance::get(&sa.sender);
-				user_liquidity_balance = user_liquidity_balance + liquidity;
-
-
-				let updated_token_balance = user_token_balance - amount_token;
-				let updated_eth_balance = EthBalance::get(&sa.sender) - amount_eth;
-
-				TokenBalance::insert(&sa.sender, &updated_token_balance);
-				EthBalance::insert(&sa.sender, updated_eth_balance);
-
-				// Updating pool liquidity parameters
-				LiquidityBalance::insert(&sa.sender, &user_liquidity_balance);
-				PoolETHLiquidity::set(pool_eth_liquidity + amount_eth);
-				PoolTokenLiquidity::set(pool_token_liquidity + amount_token);
-				TotalSupply::set(total_supply + liquidity);
-				Ok(ContractEvent::AddLiquidity(sa.sender.encode(), user_liquidity_balance.into()))
-
 			}
+    }
+}
 
-			/// RemoveLiquidity function
-			/// Amount means value in eth
-			/// @return SenderAmount with real numbers to be withdrawn, else ContractError
-			fn remove_liquidity(sa: SenderAmount) -> Result<ContractEvent, ContractError>{
-				let amount_to_remove =  get_min_user_liquidity_balance(&sa);
-				let total_supply = TotalSupply::get();
-
-				if total_supply == Uint256::from(0) {
-					return  Err(ContractError("Not enough liquidity in pool"));
-				}
-
-				let pool_eth_liquidity = PoolETHLiquidity::get();
-				let pool_token_liquidity = PoolTokenLiquidity::get();
-
-
-				let amount_eth_to_return = pool_eth_liquidity * amount_to_remove / total_supply;
-				let amount_token_to_return = pool_token_liquidity * amount_to_remove / total_supply;
-
-				let updated_total_supply = total_supply - amount_to_remove;
+impl<T: Trait> Module<T> {
+	/// Spot price of the pool: base units of token per wei, scaled by 1e18
+	pub fn spot_price(token: &EthAddress) -> Option<Price> {
+		Price::from_reserves(PoolTokenLiquidity::get(token), PoolETHLiquidity::get(token))
+	}
 
-				let mut updated_user_liquidity_balance = LiquidityBalance::get(&sa.sender);
-				updated_user_liquidity_balance = updated_user_liquidity_balance - amount_to_remove;
+	/// Price of 1 ETH in whole tokens, scaled by 1e18, token decimals are taken into account
+	pub fn token_price(token: &EthAddress) -> Option<Uint256> {
+		let decimals = Self::token_decimals(token)?;
+		Self::spot_price(token)?.to_decimal(decimals)
+	}
 
-				let user_eth_balance = TokenBalance::get(&sa.sender);
-				let user_token_balance = EthBalance::get(&sa.sender);
+	/// Weight of block events sync, it scales with number of blocks and contract methods
+	pub fn sync_weight(blocks: &[BlockEvents]) -> Weight {
+		let methods = blocks.iter().map(|be| be.methods.len() as Weight).sum::<Weight>();
+		let blocks = blocks.len() as Weight;
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads_writes(6, 8).saturating_mul(blocks))
+			.saturating_add(T::DbWeight::get().reads_writes(6, 7).saturating_mul(methods))
+	}
 
-				LiquidityBalance::insert(&sa.sender, updated_user_liquidity_balance);
-				PoolETHLiquidity::set(pool_eth_liquidity - amount_eth_to_return);
-				PoolTokenLiquidity::set(pool_token_liquidity - amount_token_to_return);
-				TotalSupply::set(updated_total_supply);
+	/// Next ethereum block to sync, None if nothing is synced and start block is not configured
+	pub fn next_eth_block() -> Option<u32> {
+		let last_synced_block = EthLastSyncedBlock::get();
+		if last_synced_block > 0 {
+			Some(last_synced_block + 1)
+		} else if EthStartBlock::get() > 0 {
+			Some(EthStartBlock::get())
+		} else {
+			None
+		}
+	}
 
-				EthBalance::insert(&sa.sender, &user_eth_balance);
-				TokenBalance::insert(&sa.sender, &user_token_balance);
-				Ok(ContractEvent::RemoveLiquidity(sa.sender.encode(), amount_to_remove.into()))
-			}
+	/// State of ethereum sync
+	pub fn get_sync_status() -> SyncStatus {
+		SyncStatus {
+			last_synced_block: EthLastSyncedBlock::get(),
+			next_block: Self::next_eth_block(),
+			start_block: EthStartBlock::get(),
+			confirmation_depth: EthConfirmationDepth::get(),
+			halted: EthSyncHalted::get(),
+		}
+	}
 
-			/// ==================== ::CONTRACT HELPERS:: ==========================
-			fn get_min_user_token_balance(sa: &SenderAmount) -> Uint256 {
-				let user_token_balance = TokenBalance::get(&sa.sender);
-				cmp::min(sa.amount, user_token_balance)
+	/// Records relayer votes for consecutive blocks and applies them in order
+	/// Block is applied only when enough relayers submitted identical content and
+	/// all previous blocks are applied, votes for next blocks are kept till then
+	fn sync_blocks(who: &T::AccountId, blocks: Vec<BlockEvents>) -> DispatchResult {
+		ensure!(!Self::eth_sync_halted(), Error::<T>::EthSyncHalted);
+		ensure!(blocks.len() as u32 <= MAX_ETH_SYNC_BATCH_SIZE, Error::<T>::InvalidBatch);
+
+		// Get block number of incoming message
+		let first_block = blocks.first().map(|be| be.block_number).ok_or(Error::<T>::InvalidBatch)?;
+
+		// Compare with last synced block on-chain
+		// It allow to update only the next block, the first block should be the start block
+		let next_block = Self::next_eth_block().ok_or(Error::<T>::EthStartBlockNotSet)?;
+		ensure!(first_block == next_block, Error::<T>::UnexpectedEthBlock);
+		ensure!(
+			blocks.windows(2).all(|w| w[1].block_number == w[0].block_number + 1),
+			Error::<T>::InvalidBatch
+		);
+		// Dispatch is not transactional, so votes are checked before any block is applied
+		for be in &blocks {
+			Self::ensure_can_vote(who, be)?;
+		}
+
+		let mut applying = true;
+		for be in blocks {
+			let reached_threshold = Self::vote_for_block(who, &be)?;
+			if !applying || !reached_threshold {
+				applying = false;
+				continue;
 			}
 
-			fn get_min_user_eth_balance(sa: &SenderAmount) -> Uint256 {
-				let user_eth_balance = EthBalance::get(&sa.sender);
-				cmp::min(sa.amount, user_eth_balance)
+			// Relayers agreed on the block which is not a child of the last synced one,
+			// events are not applied until root rolls sync back
+			if !Self::links_to_last_synced(&be) {
+				Self::halt_eth_sync(&be);
+				return Ok(());
 			}
 
-			fn get_min_user_liquidity_balance(sa: &SenderAmount) -> Uint256 {
-				let user_liquidity_token_balance = LiquidityBalance::get(&sa.sender);
-				cmp::min(sa.amount, user_liquidity_token_balance)
-			}
+			Self::apply_block_events(&be);
+			Self::clear_block_votes(be.block_number);
+			Self::prune_processed_logs(be.block_number);
 
-			fn get_ratio() -> Uint256 {
-				let token_liquidity = PoolTokenLiquidity::get();
-				let eth_liquidity = PoolETHLiquidity::get();
-				if eth_liquidity.clone() == Uint256::from(0) {
-					return INITIAL_RATIO.into();
-				}
+			EthLastSyncedBlock::put(be.block_number);
+			Self::record_eth_block_hash(be.block_number, be.block_hash);
+			Self::deposit_event(RawEvent::EthBlockSynced(be.block_number));
+		}
+		Ok(())
+	}
 
-				token_liquidity / eth_liquidity
+	/// Applies contract method in storage transaction, its changes are reverted if it fails
+	fn apply_method_atomically(method: ContractMethod, eth_block: u32) -> Result<ContractEvent, ContractError> {
+		with_transaction(|| {
+			let res = Self::apply_method(method, eth_block);
+			if res.is_ok() {
+				TransactionOutcome::Commit(res)
+			} else {
+				TransactionOutcome::Rollback(res)
 			}
+		})
+	}
 
-        	// Get block number of incoming message
-        	let block_to_sync = be.block_number;
-        	let last_synced_block = EthLastSyncedBlock::get();
-
-        	// Compare with last synced block on-chain
-        	// Adding new info only if it's greater, otherwise finish with error
-        	// It allow to update only the next block,
-        	if last_synced_block == 0 || block_to_sync == last_synced_block + 1 {
-
-        		// Iterate by all commands in block
-				for cmd in be.methods.clone() {
-					let res = match cmd {
-						DepositToken(sa) =>  deposit_token(sa),
-						DepositETH(sa) => deposit_eth(sa),
-						WithdrawETH(sa) => withdraw_eth(sa),
-						WithdrawToken(sa) => withdraw_token(sa),
-						SwapToToken(sa) => swap_to_token(sa),
-						SwapToETH(sa) => swap_to_eth(sa),
-						AddLiquidity(sa) => add_liquidity(sa),
-						RemoveLiquidity(sa) => remove_liquidity(sa),
-					};
-
-					match res {
-						Ok(e) => match e {
-							ContractEvent::DepositedToken(s, a) => Self::deposit_event(RawEvent::DepositedToken(s, a)),
-							ContractEvent::DepositedETH(s, a) => Self::deposit_event(RawEvent::DepositedETH(s, a)),
-							ContractEvent::WithdrawToken(s, a) => Self::deposit_event(RawEvent::WithdrawToken(s, a)),
-							ContractEvent::WithdrawETH(s, a) => Self::deposit_event(RawEvent::WithdrawETH(s, a)),
-							ContractEvent::SwapToToken(s, a) => Self::deposit_event(RawEvent::SwapToToken(s, a)),
-							ContractEvent::SwapToETH(s, a) => Self::deposit_event(RawEvent::SwapToETH(s, a)),
-							ContractEvent::AddLiquidity(s, a) => Self::deposit_event(RawEvent::AddLiquidity(s, a)),
-							ContractEvent::RemoveLiquidity(s, a) => Self::deposit_event(RawEvent::RemoveLiquidity(s, a)),
-						}
-						Err(err) => {
-							debug::error!("{:}", err.0);
-						}
-					}
+	/// Runs methods one after another as if they were included in the next ethereum block,
+	/// all storage changes are discarded
+	pub fn simulate(methods: Vec<ContractMethod>) -> Vec<Result<ContractEvent, ContractError>> {
+		let eth_block = Self::next_eth_block().unwrap_or_else(EthLastSyncedBlock::get);
+		with_transaction(|| {
+			let results = methods.into_iter()
+				.map(|method| Self::apply_method_atomically(method, eth_block))
+				.collect();
+			TransactionOutcome::Rollback(results)
+		})
+	}
 
+	/// Updates state based on methods which block events contain
+	fn apply_block_events(be: &BlockEvents) {
+		// Swap deadlines are checked against ethereum block which contains the swap,
+		// failures are reported with the block number
+		let eth_block = be.block_number;
+
+		// Iterate by all commands in block
+		// Each command is applied atomically: its storage changes are reverted if it fails
+		for (index, (log_id, cmd)) in be.methods.iter().cloned().enumerate() {
+			// Log could be submitted again in another block, e.g. after sync is rolled back
+			if Self::is_log_processed(&log_id) {
+				debug::warn!("Log {} is already processed, skipping {:?}", log_id, cmd);
+				continue;
+			}
+			Self::mark_log_processed(log_id, eth_block);
+
+			let res = Self::apply_method_atomically(cmd, eth_block);
+
+			match res {
+				Ok(e) => match e {
+					ContractEvent::DepositedToken(s, t, a) => Self::deposit_event(RawEvent::DepositedToken(log_id, s, t, a)),
+					ContractEvent::DepositedETH(s, a) => Self::deposit_event(RawEvent::DepositedETH(log_id, s, a)),
+					ContractEvent::WithdrawToken(s, t, a) => Self::deposit_event(RawEvent::WithdrawToken(log_id, s, t, a)),
+					ContractEvent::WithdrawETH(s, a) => Self::deposit_event(RawEvent::WithdrawETH(log_id, s, a)),
+					ContractEvent::SwapToToken(s, t, a) => Self::deposit_event(RawEvent::SwapToToken(log_id, s, t, a)),
+					ContractEvent::SwapToETH(s, t, a) => Self::deposit_event(RawEvent::SwapToETH(log_id, s, t, a)),
+					ContractEvent::AddLiquidity(s, t, a) => Self::deposit_event(RawEvent::AddLiquidity(log_id, s, t, a)),
+					ContractEvent::RemoveLiquidity(s, t, a) => Self::deposit_event(RawEvent::RemoveLiquidity(log_id, s, t, a)),
+				}
+				Err(err) => {
+					debug::error!("{:?} failed: {:?}", cmd, err);
+					Self::deposit_event(RawEvent::MethodFailed(eth_block, index as u32, log_id, cmd.sender(), cmd.kind(), err));
 				}
-
-				EthLastSyncedBlock::put(block_to_sync);
-				Self::deposit_event(RawEvent::EthBlockSynced(block_to_sync));
-        		Ok(())
-        	} else {
-        		Err(DispatchError::Other("This block is already synced!"))
-        	}
-        }
-
-        // Offchain worker runs after each block
-		fn offchain_worker(_block_number: T::BlockNumber) {
-			Self::offchain_eth_sync();
 			}
-    }
-}
 
+		}
+	}
+}
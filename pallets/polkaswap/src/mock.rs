@@ -0,0 +1,265 @@
+//! Mock runtime for polkaswap pallet tests.
+
+use codec::{Decode, Encode};
+use ethabi::{Address, Hash};
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, weights::Weight};
+use sp_core::{H256, sr25519::{self, Signature}};
+use sp_runtime::{
+	BuildStorage, Perbill,
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup, Verify},
+};
+
+use crate::{Call, GenesisConfig, Module, RawEvent, Trait, crypto};
+use crate::entities::{BlockEvents, ContractMethod, EthAddress, Hash256, LogId, SenderAmount, SwapOrder, Uint256};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+mod polkaswap {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		polkaswap<T>,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = sr25519::Public;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+type Extrinsic = TestXt<Call<Test>, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test where
+	Call<Test>: From<LocalCall>,
+{
+	type OverarchingCall = Call<Test>;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test where
+	Call<Test>: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call<Test>,
+		_public: <Signature as Verify>::Signer,
+		_account: sr25519::Public,
+		nonce: u64,
+	) -> Option<(Call<Test>, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+parameter_types! {
+	pub const EthProviderEndpoint: &'static str = "http://localhost:8545";
+}
+
+impl Trait for Test {
+	type AuthorityId = crypto::TestAuthId;
+	type Call = Call<Test>;
+	type Event = TestEvent;
+	type EthProviderEndpoint = EthProviderEndpoint;
+}
+
+pub type System = frame_system::Module<Test>;
+pub type PolkaSwap = Module<Test>;
+
+/// Vault deployment block, sync starts from it
+pub const START_BLOCK: u32 = 100;
+
+/// Liquidity which is locked forever at first add liquidity
+pub const MINIMAL_LIQUIDITY: u128 = 1000;
+
+/// Initial price of empty pools: whole tokens per 1 ETH
+pub const INITIAL_RATIO: u128 = 1000;
+
+pub const ETH: u128 = 1_000_000_000_000_000_000;
+pub const DAI: u128 = 1_000_000_000_000_000_000;
+pub const USDC: u128 = 1_000_000;
+
+pub fn relayer(n: u8) -> sr25519::Public {
+	sr25519::Public::from_raw([n; 32])
+}
+
+pub fn eth_address(n: u8) -> EthAddress {
+	EthAddress::from(Address::repeat_byte(n))
+}
+
+pub fn vault() -> EthAddress {
+	eth_address(0xaa)
+}
+
+/// Registered token with 18 decimals
+pub fn dai() -> EthAddress {
+	eth_address(0xda)
+}
+
+/// Registered token with 6 decimals
+pub fn usdc() -> EthAddress {
+	eth_address(0xcc)
+}
+
+/// Hash of ethereum block on canonical chain
+pub fn block_hash(number: u32) -> Hash256 {
+	Hash256::from(Hash::from_low_u64_be(number as u64))
+}
+
+/// Hash of ethereum block on another fork
+pub fn fork_hash(number: u32) -> Hash256 {
+	Hash256::from(Hash::from_low_u64_be(0xf000_0000 + number as u64))
+}
+
+pub fn log_id(tx: u64, log_index: u32) -> LogId {
+	LogId { tx_hash: Hash256::from(Hash::from_low_u64_be(tx)), log_index }
+}
+
+/// Block of canonical chain with methods emitted by logs of consecutive transactions
+pub fn block(number: u32, methods: Vec<ContractMethod>) -> BlockEvents {
+	BlockEvents {
+		block_number: number,
+		block_hash: block_hash(number),
+		parent_hash: block_hash(number - 1),
+		methods: methods.into_iter()
+			.enumerate()
+			.map(|(i, method)| (log_id(number as u64 * 1000 + i as u64, 0), method))
+			.collect(),
+	}
+}
+
+/// Block of another fork which parent is not on canonical chain
+pub fn fork_block(number: u32, methods: Vec<ContractMethod>) -> BlockEvents {
+	BlockEvents {
+		block_hash: fork_hash(number),
+		parent_hash: fork_hash(number - 1),
+		..block(number, methods)
+	}
+}
+
+pub fn sender_amount(sender: EthAddress, amount: u128) -> SenderAmount {
+	SenderAmount { sender, amount: Uint256::from(amount) }
+}
+
+pub fn deposit_eth(sender: EthAddress, amount: u128) -> ContractMethod {
+	ContractMethod::DepositETH(sender_amount(sender, amount))
+}
+
+pub fn deposit_token(token: EthAddress, sender: EthAddress, amount: u128) -> ContractMethod {
+	ContractMethod::DepositToken(token, sender_amount(sender, amount))
+}
+
+pub fn withdraw_eth(sender: EthAddress, amount: u128) -> ContractMethod {
+	ContractMethod::WithdrawETH(sender_amount(sender, amount))
+}
+
+pub fn withdraw_token(token: EthAddress, sender: EthAddress, amount: u128) -> ContractMethod {
+	ContractMethod::WithdrawToken(token, sender_amount(sender, amount))
+}
+
+pub fn swap_to_token(token: EthAddress, sender: EthAddress, amount: u128) -> ContractMethod {
+	ContractMethod::SwapToToken(token, sender_amount(sender, amount))
+}
+
+pub fn swap_order(sender: EthAddress, amount: u128, limit: u128, deadline: u32) -> SwapOrder {
+	SwapOrder {
+		sender,
+		amount: Uint256::from(amount),
+		limit: Uint256::from(limit),
+		deadline: Uint256::from(deadline as u128),
+	}
+}
+
+pub fn swap_exact_eth_for_tokens(token: EthAddress, sender: EthAddress, amount: u128, limit: u128, deadline: u32) -> ContractMethod {
+	ContractMethod::SwapExactETHForTokens(token, swap_order(sender, amount, limit, deadline))
+}
+
+pub fn swap_eth_for_exact_tokens(token: EthAddress, sender: EthAddress, amount: u128, limit: u128, deadline: u32) -> ContractMethod {
+	ContractMethod::SwapETHForExactTokens(token, swap_order(sender, amount, limit, deadline))
+}
+
+pub fn swap_exact_tokens_for_eth(token: EthAddress, sender: EthAddress, amount: u128, limit: u128, deadline: u32) -> ContractMethod {
+	ContractMethod::SwapExactTokensForETH(token, swap_order(sender, amount, limit, deadline))
+}
+
+pub fn swap_tokens_for_exact_eth(token: EthAddress, sender: EthAddress, amount: u128, limit: u128, deadline: u32) -> ContractMethod {
+	ContractMethod::SwapTokensForExactETH(token, swap_order(sender, amount, limit, deadline))
+}
+
+pub fn add_liquidity(token: EthAddress, sender: EthAddress, amount: u128) -> ContractMethod {
+	ContractMethod::AddLiquidity(token, sender_amount(sender, amount))
+}
+
+/// Events deposited by polkaswap pallet
+pub fn polkaswap_events() -> Vec<RawEvent<sr25519::Public, u64>> {
+	System::events().into_iter()
+		.filter_map(|record| match record.event {
+			TestEvent::polkaswap(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Three relayers, two of them should attest block
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> {
+		vault_contract_address: vault(),
+		tokens: vec![(dai(), 18), (usdc(), 6)],
+		initial_ratio: INITIAL_RATIO,
+		minimal_liquidity: MINIMAL_LIQUIDITY,
+		eth_confirmation_depth: 3,
+		eth_start_block: START_BLOCK,
+		eth_sync_batch_size: 10,
+		processed_logs_depth: 0,
+		relayers: vec![relayer(1), relayer(2), relayer(3)],
+		relayer_threshold: 2,
+		eth_signers: vec![],
+	}.assimilate_storage(&mut storage).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	// Events are not deposited at genesis block
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -1,50 +1,130 @@
-use frame_system::offchain::{Signer, SendSignedTransaction};
-use frame_support::debug;
+use frame_system::offchain::{AppCrypto, Signer, SendSignedTransaction};
+use frame_support::{debug, StorageMap, StorageValue};
+use sp_core::ecdsa;
+use sp_std::{cmp, prelude::*};
 
-use super::{Error, Module, Trait, Call};
-use sp_runtime::offchain::storage::StorageValueRef;
+use super::{Error, Module, Trait, Call, ETH_KEY_TYPE, FETCH_TIMEOUT_PERIOD, LOCK_BLOCK_EXPIRATION,
+			LOCK_TIMEOUT_EXPIRATION, MAX_ETH_SYNC_BATCH_SIZE, MAX_ETH_SYNC_REQUESTS, WithdrawalBlock,
+			WithdrawalNonce, Withdrawals};
+use crate::entities::EthAddress;
+use crate::eth_crypto;
+use crate::eth_signer::{self, eth_address};
+use sp_runtime::offchain::{Duration, storage::StorageValueRef, storage_lock::{BlockAndTime, StorageLock}};
+use sp_runtime::RuntimeAppPublic;
+use sp_runtime::traits::{BlockNumberProvider, IdentifyAccount};
 
-/// Number of block height (confirmations) to be synced with polkaswap
-const SYNC_DELAY : u32 = 3;
+/// Key for KV storage to save submitted block: (eth block number, block number of submission)
+const LS_IN_FLIGHT_BLOCK_KEY: &[u8] = b"offchain-polkaswap::in_flight_block";
 
-/// Key for KV storage to save last synced block
-const LS_LAST_BLOCK_KEY: &[u8] = b"offchain-polkaswap::last_synced_block";
+/// Key for KV storage lock which guards eth sync from concurrent offchain worker runs
+const LS_SYNC_LOCK_KEY: &[u8] = b"offchain-polkaswap::sync_lock";
+
+/// Number of blocks after which submitted eth block is considered dropped and submitted again
+const IN_FLIGHT_BLOCK_EXPIRATION: u32 = 10;
+
+/// Key for KV storage to save nonce of the next withdrawal to sign
+const LS_NEXT_SIGN_NONCE_KEY: &[u8] = b"offchain-polkaswap::next_sign_nonce";
+
+/// Maximum number of withdrawals signed in one offchain worker run
+const MAX_WITHDRAWALS_TO_SIGN: u64 = 32;
 
 impl<T: Trait> Module<T> {
 	/// Offchain Eth Sync method get the latest info from Ethereum and send tx on-chain
 	pub fn offchain_eth_sync() -> Result<(), Error<T>> {
 
+		// Only authorized relayers submit blocks, so there is nothing to do without their keys
+		let signer = Self::relayer_signer();
+		if !signer.can_sign() {
+			debug::info!("No local relayer account, skipping eth sync");
+			return Err(<Error<T>>::NoLocalAcctForSigning);
+		}
+
+		// Halted sync waits for root to roll it back
+		if Self::eth_sync_halted() {
+			debug::warn!("Eth sync is halted, skipping eth sync");
+			return Ok(());
+		}
+
+		// Offchain workers of subsequent blocks could run concurrently,
+		// lock is released when guard is dropped or after both block and time deadlines
+		// Time deadline covers the slowest run, when each request times out on every provider
+		// Header of each block in the batch is fetched too
+		let providers = Self::eth_provider_urls().len() as u64;
+		let requests = MAX_ETH_SYNC_REQUESTS + cmp::min(Self::eth_sync_batch_size(), MAX_ETH_SYNC_BATCH_SIZE) as u64;
+		let lock_timeout = FETCH_TIMEOUT_PERIOD * requests * providers + LOCK_TIMEOUT_EXPIRATION;
+		let mut lock = StorageLock::<BlockAndTime<Self>>::with_block_and_time_deadline(
+			LS_SYNC_LOCK_KEY,
+			LOCK_BLOCK_EXPIRATION,
+			Duration::from_millis(lock_timeout),
+		);
+		let _guard = match lock.try_lock() {
+			Ok(guard) => guard,
+			Err(_) => {
+				debug::info!("Eth sync is running in another offchain worker");
+				return Ok(());
+			}
+		};
+
 		// Getting the number of last block from ethereum network
 		let last_block_eth = Self::get_last_eth_block()?;
 
 		debug::info!("Last block:{}", &last_block_eth);
 
-		// Getting last saved blocknumber in local storage
-		// If there is no info, we set it as the closest block which should be updated
-		let last_block_saved = if let Some(lbs) = Self::storage_get_last_block() {
-			lbs
-		} else { last_block_eth - SYNC_DELAY -1 };
+		// Number of confirmations is configured on-chain, it could exceed the chain length
+		// on a fresh network, so there is no confirmed block to sync yet
+		let sync_delay = Self::eth_confirmation_depth();
+		let last_confirmed_block = last_block_eth.saturating_sub(sync_delay);
+		if last_confirmed_block == 0 {
+			return Ok(());
+		}
+
+		// Next block to sync is based on on-chain state, so all relayers vote for the same block
+		// Sync starts from the start block and all vault events are replayed,
+		// nothing is synced until root sets it
+		let current_block = match Self::next_eth_block() {
+			Some(block) => block,
+			None => {
+				debug::warn!("Eth start block is not set, skipping eth sync");
+				return Ok(());
+			}
+		};
 
-		// Check that there is blocks which are needed to sync. SYNC_DELAY is needed to
+		// Check that there is blocks which are needed to sync. sync_delay is needed to
 		// set up minimal confirmations. We assume that there is no changes in Ethereum
-		// after SYNC_DELAY blocks
-		if last_block_saved >= last_block_eth - SYNC_DELAY {
+		// after sync_delay blocks
+		if current_block > last_confirmed_block {
 			return Ok(());
 		}
 
-		// Set current block to next one we need to update
-		let current_block = last_block_saved + 1;
+		// Getting last submitted blocknumber in local storage
+		// Transaction with this relayer vote is in flight or it's included and waiting for other relayers
+		// Block is submitted again when transaction expires or sync was rolled back to another block
+		let block_number = <frame_system::Module<T>>::block_number();
+		if let Some((in_flight_block, submitted_at)) = Self::storage_get_in_flight_block() {
+			if in_flight_block == current_block && block_number <= submitted_at + IN_FLIGHT_BLOCK_EXPIRATION.into() {
+				return Ok(());
+			}
+		}
+
+		// Range of blocks is synced at once to catch up faster
+		let batch_size = cmp::min(cmp::max(Self::eth_sync_batch_size(), 1), MAX_ETH_SYNC_BATCH_SIZE);
+		let to_block = cmp::min(current_block + batch_size - 1, last_confirmed_block);
 
 		// Getting block events from ethereum network
-		let block_events = Self::get_block_events(current_block)?;
-		debug::info!("{:?}", &block_events);
+		let blocks_events = Self::get_blocks_events(current_block, to_block)?;
+		debug::info!("{:?}", &blocks_events);
 
+		// Block is still submitted, so relayers agree on reorg on-chain and sync is halted
+		if let Some(first) = blocks_events.first() {
+			if !Self::links_to_last_synced(first) {
+				debug::error!("Eth reorg detected: block {} is not linked to the last synced block", current_block);
+			}
+		}
 
 		// Sign transaction with getting info
-		let signer = Signer::<T, T::AuthorityId>::any_account();
 		let result = signer.send_signed_transaction(|_acct|
 			// This is the on-chain function
-			Call::sync_eth_block(block_events.clone())
+			Call::sync_eth_block_batch(blocks_events.clone())
 		);
 
 		// Display error if the signed tx fails.
@@ -55,7 +135,7 @@ impl<T: Trait> Module<T> {
 			}
 			// Transaction is sent successfully
 			debug::info!("Transaction sent!");
-			Self::storage_set_last_block(current_block);
+			Self::storage_set_in_flight_block(current_block, block_number);
 			return Ok(());
 		}
 
@@ -64,13 +144,104 @@ impl<T: Trait> Module<T> {
 		Err(<Error<T>>::NoLocalAcctForSigning)
 	}
 
-	/// Get the last block number stored in local storage
-	/// @return Optional value of block number
-	pub fn storage_get_last_block() -> Option<u32> {
+	/// Offchain Sign Withdrawals method signs sealed withdrawals with local validator keys
+	/// and sends signatures on-chain
+	pub fn offchain_sign_withdrawals() -> Result<(), Error<T>> {
+		let eth_signers = Self::eth_signers();
+
+		// Local secp256k1 keys which belong to validators
+		let keys: Vec<(ecdsa::Public, EthAddress)> = <eth_crypto::Public as RuntimeAppPublic>::all()
+			.into_iter()
+			.map(|key| key.into())
+			.filter_map(|public: ecdsa::Public| eth_address(&public).map(|address| (public, address)))
+			.filter(|(_, address)| eth_signers.contains(address))
+			.collect();
+
+		if keys.is_empty() {
+			return Ok(());
+		}
+
+		let from_nonce = Self::storage_get_next_sign_nonce();
+		let to_nonce = cmp::min(from_nonce + MAX_WITHDRAWALS_TO_SIGN, WithdrawalNonce::get());
+
+		let mut signatures: Vec<(u64, ecdsa::Signature)> = vec![];
+		let mut next_nonce = from_nonce;
+
+		for nonce in from_nonce..to_nonce {
+			// Only sealed withdrawals are signed, so they are already included in merkle root
+			if !WithdrawalBlock::<T>::contains_key(nonce) {
+				break;
+			}
+			next_nonce = nonce + 1;
+
+			let withdrawal = match Withdrawals::get(nonce) {
+				Some(w) => w,
+				None => continue,
+			};
+
+			let digest = withdrawal.eth_signed_message_hash();
+			let signed = Self::withdrawal_signatures(nonce);
+
+			for (public, address) in keys.iter() {
+				if signed.iter().any(|(signer, _)| signer == address) {
+					continue;
+				}
+				match eth_signer::eth_signer::sign_prehashed(ETH_KEY_TYPE, public, digest.as_fixed_bytes()) {
+					Some(signature) => signatures.push((nonce, signature)),
+					None => debug::error!("Cant sign withdrawal {} with key {:?}", nonce, address),
+				}
+			}
+		}
+
+		if signatures.is_empty() {
+			Self::storage_set_next_sign_nonce(next_nonce);
+			return Ok(());
+		}
+
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+		let result = signer.send_signed_transaction(|_acct|
+			Call::submit_withdrawal_signatures(signatures.clone())
+		);
+
+		if let Some((acc, res)) = result {
+			if res.is_err() {
+				debug::error!("failure: offchain_signed_tx: tx sent: {:?}", acc.id);
+				return Err(<Error<T>>::OffchainSignedTxError);
+			}
+			debug::info!("Withdrawal signatures sent!");
+			Self::storage_set_next_sign_nonce(next_nonce);
+			return Ok(());
+		}
+
+		debug::error!("No local account available");
+		Err(<Error<T>>::NoLocalAcctForSigning)
+	}
+
+	/// Signer which uses local keys of authorized relayers only
+	fn relayer_signer() -> Signer<T, T::AuthorityId> {
+		let relayers = Self::relayers();
+
+		let keys = <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+			.into_iter()
+			.map(|key| {
+				let generic_public =
+					<T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic::from(key);
+				let public: T::Public = generic_public.into();
+				public
+			})
+			.filter(|public| relayers.contains(&public.clone().into_account()))
+			.collect::<Vec<T::Public>>();
+
+		Signer::<T, T::AuthorityId>::any_account().with_filter(keys)
+	}
+
+	/// Get the last submitted block number stored in local storage
+	/// @return Optional value of eth block number and block number of submission
+	pub fn storage_get_in_flight_block() -> Option<(u32, T::BlockNumber)> {
 		// Create a reference to Local Storage value.
 		// Since the local storage is common for all offchain workers, it's a good practice
 		// to prepend our entry with the pallet name.
-		let s_info = StorageValueRef::persistent(LS_LAST_BLOCK_KEY);
+		let s_info = StorageValueRef::persistent(LS_IN_FLIGHT_BLOCK_KEY);
 
 		// Local storage is persisted and shared between runs of the offchain workers,
 		// offchain workers may run concurrently. We can use the `mutate` function to
@@ -81,18 +252,38 @@ impl<T: Trait> Module<T> {
 		// the storage comprehensively.
 		//
 		// Ref: https://substrate.dev/rustdocs/v2.0.0/sp_runtime/offchain/storage/struct.StorageValueRef.html
-		if let Some(Some(ls)) = s_info.get::<u32>() {
-			// gh-info has already been fetched. Return early.
-			debug::info!("last block stored: {:?}", ls);
+		if let Some(Some(ls)) = s_info.get::<(u32, T::BlockNumber)>() {
+			debug::info!("in flight block stored: {:?}", ls);
 			Some(ls)
 		} else {
 			None
 		}
 	}
 
-	/// Stores the last block number stored in local storage
-	pub fn storage_set_last_block(block_num: u32) {
-		let s_info = StorageValueRef::persistent(LS_LAST_BLOCK_KEY);
-		s_info.set(&block_num);
+	/// Stores the submitted block number and block number of submission in local storage
+	pub fn storage_set_in_flight_block(block_num: u32, submitted_at: T::BlockNumber) {
+		let s_info = StorageValueRef::persistent(LS_IN_FLIGHT_BLOCK_KEY);
+		s_info.set(&(block_num, submitted_at));
+	}
+
+	/// Get nonce of the next withdrawal to sign stored in local storage
+	pub fn storage_get_next_sign_nonce() -> u64 {
+		let s_info = StorageValueRef::persistent(LS_NEXT_SIGN_NONCE_KEY);
+		s_info.get::<u64>().flatten().unwrap_or(0)
+	}
+
+	/// Stores nonce of the next withdrawal to sign in local storage
+	pub fn storage_set_next_sign_nonce(nonce: u64) {
+		let s_info = StorageValueRef::persistent(LS_NEXT_SIGN_NONCE_KEY);
+		s_info.set(&nonce);
+	}
+}
+
+/// Block number provider for storage lock deadline
+impl<T: Trait> BlockNumberProvider for Module<T> {
+	type BlockNumber = T::BlockNumber;
+
+	fn current_block_number() -> Self::BlockNumber {
+		<frame_system::Module<T>>::block_number()
 	}
 }
@@ -0,0 +1,113 @@
+use frame_support::{ensure, IterableStorageDoubleMap, IterableStorageMap, StorageDoubleMap, StorageMap};
+use sp_std::prelude::*;
+
+use crate::{amm, ContractError, Error, EthBalance, LiquidityBalance, Module, PoolETHLiquidity, PoolTokenLiquidity,
+			TokenBalance, Tokens, TotalSupply, Trait};
+use crate::entities::{EthAddress, LiquidityPosition, Pool, SwapDirection, UserBalances, UserTokenBalance, Uint256};
+
+impl<T: Trait> Module<T> {
+	/// ETH balance and balances in pools of all registered tokens
+	pub fn get_balances(user: &EthAddress) -> UserBalances {
+		let tokens = Tokens::iter()
+			.map(|(token, _)| UserTokenBalance {
+				token,
+				balance: TokenBalance::get(user, &token),
+				liquidity: LiquidityBalance::get(user, &token),
+			})
+			.collect();
+
+		UserBalances { eth: EthBalance::get(user), tokens }
+	}
+
+	/// Pool of registered token, None if token is not registered
+	pub fn get_pool(token: &EthAddress) -> Option<Pool> {
+		if !Tokens::contains_key(token) {
+			return None;
+		}
+
+		Some(Pool {
+			token: *token,
+			token_reserve: PoolTokenLiquidity::get(token),
+			eth_reserve: PoolETHLiquidity::get(token),
+			total_supply: TotalSupply::get(token),
+			price: Self::spot_price(token),
+		})
+	}
+
+	/// Output amount for exact input amount, fee is taken into account
+	pub fn quote_exact_input(token: &EthAddress, direction: SwapDirection, amount_in: Uint256) -> Result<Uint256, ContractError> {
+		let (reserve_in, reserve_out) = Self::swap_reserves(token, direction)?;
+		amm::get_amount_out(amount_in, reserve_in, reserve_out)
+	}
+
+	/// Input amount for exact output amount, fee is taken into account
+	pub fn quote_exact_output(token: &EthAddress, direction: SwapDirection, amount_out: Uint256) -> Result<Uint256, ContractError> {
+		let (reserve_in, reserve_out) = Self::swap_reserves(token, direction)?;
+		amm::get_amount_in(amount_out, reserve_in, reserve_out)
+	}
+
+	/// Pool reserves which user would get for all its liquidity tokens
+	pub fn get_liquidity_position(user: &EthAddress, token: &EthAddress) -> Option<LiquidityPosition> {
+		let pool = Self::get_pool(token)?;
+		let liquidity = LiquidityBalance::get(user, token);
+
+		let share = |reserve: Uint256| reserve.checked_mul(liquidity)
+			.and_then(|amount| amount.checked_div(pool.total_supply))
+			.unwrap_or_default();
+
+		Some(LiquidityPosition {
+			token: *token,
+			liquidity,
+			token_amount: share(pool.token_reserve),
+			eth_amount: share(pool.eth_reserve),
+		})
+	}
+
+	/// Moves pool and balances of all its users to new token address
+	/// Balances are iterated for all tokens, so at most max_balances of them are read to bound weight,
+	/// nothing is changed if there are more token and liquidity balances
+	pub(crate) fn move_pool(token: &EthAddress, address: &EthAddress, max_balances: u32) -> Result<(), Error<T>> {
+		let max_balances = max_balances as usize;
+
+		// Storage is not changed while it's iterated
+		let balances: Vec<(EthAddress, EthAddress, Uint256)> = TokenBalance::iter()
+			.take(max_balances + 1)
+			.collect();
+		ensure!(balances.len() <= max_balances, Error::<T>::TooManyBalances);
+		let liquidity: Vec<(EthAddress, EthAddress, Uint256)> = LiquidityBalance::iter()
+			.take(max_balances - balances.len() + 1)
+			.collect();
+		ensure!(balances.len() + liquidity.len() <= max_balances, Error::<T>::TooManyBalances);
+
+		if let Some(decimals) = Tokens::take(token) {
+			Tokens::insert(address, decimals);
+		}
+		PoolTokenLiquidity::insert(address, PoolTokenLiquidity::take(token));
+		PoolETHLiquidity::insert(address, PoolETHLiquidity::take(token));
+		TotalSupply::insert(address, TotalSupply::take(token));
+
+		for (user, _, balance) in balances.into_iter().filter(|(_, t, _)| t == token) {
+			TokenBalance::remove(&user, token);
+			TokenBalance::insert(&user, address, balance);
+		}
+		for (user, _, balance) in liquidity.into_iter().filter(|(_, t, _)| t == token) {
+			LiquidityBalance::remove(&user, token);
+			LiquidityBalance::insert(&user, address, balance);
+		}
+		Ok(())
+	}
+
+	/// Reserves of the pool in swap direction: (reserve in, reserve out)
+	fn swap_reserves(token: &EthAddress, direction: SwapDirection) -> Result<(Uint256, Uint256), ContractError> {
+		if !Tokens::contains_key(token) {
+			return Err(ContractError::TokenNotRegistered);
+		}
+
+		let token_reserve = PoolTokenLiquidity::get(token);
+		let eth_reserve = PoolETHLiquidity::get(token);
+		Ok(match direction {
+			SwapDirection::EthToToken => (eth_reserve, token_reserve),
+			SwapDirection::TokenToEth => (token_reserve, eth_reserve),
+		})
+	}
+}
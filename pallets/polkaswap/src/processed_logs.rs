@@ -0,0 +1,48 @@
+use frame_support::{StorageMap, StorageValue};
+use sp_std::cmp;
+
+use crate::{BlockProcessedLogs, EthStartBlock, MAX_ETH_SYNC_BATCH_SIZE, Module, ProcessedLogs, ProcessedLogsDepth,
+			ProcessedLogsPrunedBlock, Trait};
+use crate::entities::LogId;
+
+impl<T: Trait> Module<T> {
+	pub(crate) fn is_log_processed(log_id: &LogId) -> bool {
+		ProcessedLogs::contains_key(log_id)
+	}
+
+	/// Marks log as processed, logs are indexed by ethereum block for pruning
+	pub(crate) fn mark_log_processed(log_id: LogId, block_number: u32) {
+		ProcessedLogs::insert(&log_id, block_number);
+		BlockProcessedLogs::append(block_number, log_id);
+	}
+
+	/// Prunes logs of blocks deeper than ProcessedLogsDepth, zero depth keeps logs forever
+	/// At most MAX_ETH_SYNC_BATCH_SIZE + 1 blocks are pruned at once,
+	/// so pruning catches up gradually when depth is decreased
+	pub(crate) fn prune_processed_logs(block_number: u32) {
+		let depth = ProcessedLogsDepth::get();
+		if depth == 0 || block_number <= depth {
+			return;
+		}
+
+		let prune_to = block_number - depth;
+		let pruned_block = ProcessedLogsPrunedBlock::get();
+		// Nothing is pruned yet, there are no processed logs before the start block
+		let from = if pruned_block == 0 {
+			EthStartBlock::get()
+		} else {
+			pruned_block + 1
+		};
+		let to = cmp::min(prune_to, from.saturating_add(MAX_ETH_SYNC_BATCH_SIZE));
+		if from > to {
+			return;
+		}
+
+		for n in from..=to {
+			for log_id in BlockProcessedLogs::take(n) {
+				ProcessedLogs::remove(&log_id);
+			}
+		}
+		ProcessedLogsPrunedBlock::put(to);
+	}
+}
@@ -0,0 +1,84 @@
+use frame_support::{debug, ensure, StorageMap, StorageValue, dispatch::DispatchError};
+use sp_std::cmp;
+
+use crate::{BlockProcessedLogs, Error, EthBlockHashes, EthLastSyncedBlock, EthStartBlock, EthSyncHalted,
+			MAX_ETH_SYNC_BATCH_SIZE, Module, ProcessedLogsPrunedBlock, RawEvent, Trait};
+use crate::entities::{BlockEvents, Hash256};
+
+/// Number of synced ethereum blocks which hashes are kept on-chain,
+/// sync could not be rolled back deeper
+pub const ETH_BLOCK_HASHES_DEPTH: u32 = 256;
+
+impl<T: Trait> Module<T> {
+	/// Checks that block is a child of the last synced block
+	/// Blocks without stored hash (first block, blocks synced before hashes were stored) are trusted
+	pub(crate) fn links_to_last_synced(be: &BlockEvents) -> bool {
+		let last_synced_block = EthLastSyncedBlock::get();
+		if last_synced_block == 0 {
+			return true;
+		}
+
+		match EthBlockHashes::get(last_synced_block) {
+			Some(hash) => hash == be.parent_hash,
+			None => true,
+		}
+	}
+
+	/// Halts sync, block events are not applied until root rolls sync back
+	pub(crate) fn halt_eth_sync(be: &BlockEvents) {
+		let last_synced_block = EthLastSyncedBlock::get();
+		let expected_hash = EthBlockHashes::get(last_synced_block).unwrap_or_default();
+
+		debug::error!("Eth reorg detected at block {}: expected parent {}, got {}",
+					  be.block_number, expected_hash, be.parent_hash);
+
+		EthSyncHalted::put(true);
+		Self::deposit_event(RawEvent::EthReorgDetected(be.block_number, expected_hash, be.parent_hash));
+	}
+
+	/// Stores hash of synced block and prunes hashes deeper than ETH_BLOCK_HASHES_DEPTH
+	pub(crate) fn record_eth_block_hash(block_number: u32, hash: Hash256) {
+		EthBlockHashes::insert(block_number, hash);
+		if block_number > ETH_BLOCK_HASHES_DEPTH {
+			EthBlockHashes::remove(block_number - ETH_BLOCK_HASHES_DEPTH);
+		}
+	}
+
+	/// Sets last synced block back, so the next blocks are synced again from the canonical chain
+	/// Zero block restarts sync from configured start block
+	/// State changes of rolled back blocks are not reverted, so blocks with processed logs
+	/// could not be rolled back: logs which were re-mined into other blocks get new log ids
+	/// and they would be applied twice
+	pub(crate) fn rollback_to(block_number: u32) -> Result<(), DispatchError> {
+		let last_synced_block = EthLastSyncedBlock::get();
+		ensure!(block_number <= last_synced_block, Error::<T>::InvalidRollbackBlock);
+		ensure!(block_number == 0 || EthBlockHashes::contains_key(block_number), Error::<T>::InvalidRollbackBlock);
+
+		// Blocks before start block have no logs, processed logs of pruned blocks are unknown
+		let first_rolled_back = cmp::max(block_number + 1, EthStartBlock::get());
+		ensure!(
+			last_synced_block.saturating_sub(first_rolled_back) < ETH_BLOCK_HASHES_DEPTH,
+			Error::<T>::InvalidRollbackBlock
+		);
+		ensure!(
+			first_rolled_back > ProcessedLogsPrunedBlock::get() &&
+				(first_rolled_back..=last_synced_block).all(|n| BlockProcessedLogs::get(n).is_empty()),
+			Error::<T>::RollbackOverProcessedLogs
+		);
+
+		// Hashes deeper than ETH_BLOCK_HASHES_DEPTH are already pruned
+		let from = cmp::max(block_number, last_synced_block.saturating_sub(ETH_BLOCK_HASHES_DEPTH)) + 1;
+		for n in from..=last_synced_block {
+			EthBlockHashes::remove(n);
+		}
+
+		// Votes which were submitted for the halted block and blocks after it are dropped
+		for n in (last_synced_block + 1)..=(last_synced_block + MAX_ETH_SYNC_BATCH_SIZE) {
+			Self::clear_block_votes(n);
+		}
+
+		EthLastSyncedBlock::put(block_number);
+		EthSyncHalted::put(false);
+		Ok(())
+	}
+}
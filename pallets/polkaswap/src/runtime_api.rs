@@ -0,0 +1,46 @@
+//! Runtime API definition for polkaswap pallet
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+use crate::{ContractError, ContractEvent};
+use crate::entities::{ContractMethod, EthAddress, LiquidityPosition, Pool, SwapDirection, SyncStatus, Uint256,
+					  UserBalances, Withdrawal, WithdrawalBundle, WithdrawalProof};
+
+sp_api::decl_runtime_apis! {
+	/// API to claim withdrawals on Ethereum vault contract
+	pub trait WithdrawalApi<BlockNumber> where BlockNumber: Codec {
+		/// Queued withdrawals with nonces from `from_nonce`, at most `limit` items
+		fn withdrawals(from_nonce: u64, limit: u32) -> Vec<Withdrawal>;
+
+		/// Merkle proof of withdrawal, None if withdrawal is not sealed yet
+		fn withdrawal_proof(nonce: u64) -> Option<WithdrawalProof<BlockNumber>>;
+
+		/// Withdrawal with validator signatures in the form accepted by vault contract
+		fn withdrawal_bundle(nonce: u64) -> Option<WithdrawalBundle>;
+	}
+
+	/// API to query balances, pools and swap quotes without decoding raw storage
+	pub trait PolkaSwapApi {
+		/// ETH balance and balances in pools of all registered tokens
+		fn balances(user: EthAddress) -> UserBalances;
+
+		/// Reserves, liquidity tokens supply and spot price of pool, None if token is not registered
+		fn pool(token: EthAddress) -> Option<Pool>;
+
+		/// Output amount of swap with exact input amount
+		fn quote_exact_input(token: EthAddress, direction: SwapDirection, amount_in: Uint256) -> Result<Uint256, ContractError>;
+
+		/// Input amount of swap with exact output amount
+		fn quote_exact_output(token: EthAddress, direction: SwapDirection, amount_out: Uint256) -> Result<Uint256, ContractError>;
+
+		/// Pool reserves which user would get for removing its liquidity, None if token is not registered
+		fn liquidity_position(user: EthAddress, token: EthAddress) -> Option<LiquidityPosition>;
+
+		/// Last synced and next ethereum blocks, sync is halted on ethereum reorg
+		fn sync_status() -> SyncStatus;
+
+		/// Dry-run of methods as if they were included in the next ethereum block, state is not changed
+		fn simulate(methods: Vec<ContractMethod>) -> Vec<Result<ContractEvent, ContractError>>;
+	}
+}
@@ -0,0 +1,846 @@
+use frame_support::{assert_err, assert_ok, StorageValue};
+use sp_core::H256;
+use sp_runtime::DispatchError;
+
+use crate::{ContractError, Error, EthStartBlock, MAX_ETH_SYNC_BATCH_SIZE, RawEvent};
+use crate::entities::{BlockEvents, ContractMethodKind, Uint256};
+use crate::mock::*;
+
+fn eth_balance(user: u8) -> Uint256 {
+	PolkaSwap::eth_balance(eth_address(user))
+}
+
+/// Submits block by enough relayers to apply it
+fn sync(be: BlockEvents) {
+	assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), be.clone()));
+	assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(2)), be));
+}
+
+mod attestation {
+	use super::*;
+
+	#[test]
+	fn block_is_applied_when_threshold_is_reached() {
+		new_test_ext().execute_with(|| {
+			let be = block(START_BLOCK, vec![deposit_eth(eth_address(1), ETH)]);
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), be.clone()));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), 0);
+			assert_eq!(eth_balance(1), Uint256::from(0));
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(2)), be));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+			assert_eq!(eth_balance(1), Uint256::from(ETH));
+			assert!(polkaswap_events().contains(&RawEvent::EthBlockSynced(START_BLOCK)));
+		});
+	}
+
+	#[test]
+	fn only_relayers_submit_blocks() {
+		new_test_ext().execute_with(|| {
+			let be = block(START_BLOCK, vec![deposit_eth(eth_address(1), ETH)]);
+			assert_err!(
+				PolkaSwap::sync_eth_block(Origin::signed(relayer(4)), be),
+				Error::<Test>::NotAuthorizedRelayer
+			);
+		});
+	}
+
+	#[test]
+	fn conflicting_votes_are_counted_separately() {
+		new_test_ext().execute_with(|| {
+			let honest = block(START_BLOCK, vec![deposit_eth(eth_address(1), ETH)]);
+			let forged = block(START_BLOCK, vec![deposit_eth(eth_address(2), 1000 * ETH)]);
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), honest.clone()));
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(2)), forged));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), 0);
+			assert!(polkaswap_events().iter().any(|e| matches!(e, RawEvent::EthBlockVoteConflict(START_BLOCK, _, _))));
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(3)), honest));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+			assert_eq!(eth_balance(1), Uint256::from(ETH));
+			assert_eq!(eth_balance(2), Uint256::from(0));
+		});
+	}
+
+	#[test]
+	fn relayer_votes_once_for_block() {
+		new_test_ext().execute_with(|| {
+			let be = block(START_BLOCK, vec![deposit_eth(eth_address(1), ETH)]);
+			let other = block(START_BLOCK, vec![deposit_eth(eth_address(2), ETH)]);
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), be.clone()));
+			assert_err!(
+				PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), other),
+				Error::<Test>::AlreadyVoted
+			);
+
+			// The same content is accepted again, but it's counted once
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), be));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), 0);
+		});
+	}
+
+	#[test]
+	fn batch_with_rejected_vote_is_not_applied() {
+		new_test_ext().execute_with(|| {
+			let be = block(START_BLOCK, vec![deposit_eth(eth_address(1), ETH)]);
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(2)), be.clone()));
+			assert_ok!(PolkaSwap::sync_eth_block(
+				Origin::signed(relayer(1)),
+				block(START_BLOCK + 1, vec![deposit_eth(eth_address(1), ETH)])
+			));
+
+			// The first block would reach threshold, but vote for the second one is rejected
+			let other = block(START_BLOCK + 1, vec![deposit_eth(eth_address(2), ETH)]);
+			assert_err!(
+				PolkaSwap::sync_eth_block_batch(Origin::signed(relayer(1)), vec![be.clone(), other]),
+				Error::<Test>::AlreadyVoted
+			);
+			assert_eq!(PolkaSwap::eth_last_synced_block(), 0);
+			assert_eq!(PolkaSwap::relayer_votes(START_BLOCK, relayer(1)), None::<H256>);
+			assert_eq!(eth_balance(1), Uint256::from(0));
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), be));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+		});
+	}
+
+	#[test]
+	fn overlapping_batches_with_different_boundaries_are_counted_together() {
+		new_test_ext().execute_with(|| {
+			let blocks: Vec<BlockEvents> = (0..5)
+				.map(|i| block(START_BLOCK + i, vec![deposit_eth(eth_address(1), ETH)]))
+				.collect();
+
+			// Each block carries its own hashes, so its content doesn't depend on the batch range
+			assert_ok!(PolkaSwap::sync_eth_block_batch(Origin::signed(relayer(1)), blocks[0..3].to_vec()));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), 0);
+
+			assert_ok!(PolkaSwap::sync_eth_block_batch(Origin::signed(relayer(2)), blocks[0..2].to_vec()));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK + 1);
+
+			assert_ok!(PolkaSwap::sync_eth_block_batch(Origin::signed(relayer(2)), blocks[2..4].to_vec()));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK + 2);
+
+			assert_ok!(PolkaSwap::sync_eth_block_batch(Origin::signed(relayer(1)), blocks[3..5].to_vec()));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK + 3);
+			assert_eq!(PolkaSwap::eth_block_hash(START_BLOCK + 3), Some(block_hash(START_BLOCK + 3)));
+			assert_eq!(eth_balance(1), Uint256::from(4 * ETH));
+		});
+	}
+
+	#[test]
+	fn votes_of_removed_relayer_are_not_counted() {
+		new_test_ext().execute_with(|| {
+			let be = block(START_BLOCK, vec![deposit_eth(eth_address(1), ETH)]);
+			let next = block(START_BLOCK + 1, vec![deposit_eth(eth_address(1), ETH)]);
+
+			// Relayer votes for the block and the next one in a batch and it's removed then
+			assert_ok!(PolkaSwap::sync_eth_block_batch(Origin::signed(relayer(1)), vec![be.clone(), next.clone()]));
+			assert_ok!(PolkaSwap::remove_relayer(Origin::root(), relayer(1)));
+			assert_err!(
+				PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), be.clone()),
+				Error::<Test>::NotAuthorizedRelayer
+			);
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(2)), be.clone()));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), 0);
+
+			assert_ok!(PolkaSwap::sync_eth_block_batch(Origin::signed(relayer(3)), vec![be, next.clone()]));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(2)), next));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK + 1);
+		});
+	}
+
+	#[test]
+	fn threshold_is_within_relayer_set() {
+		new_test_ext().execute_with(|| {
+			assert_err!(PolkaSwap::set_relayer_threshold(Origin::root(), 0), Error::<Test>::InvalidThreshold);
+			assert_err!(PolkaSwap::set_relayer_threshold(Origin::root(), 4), Error::<Test>::InvalidThreshold);
+			assert_err!(PolkaSwap::set_relayer_threshold(Origin::signed(relayer(1)), 1), DispatchError::BadOrigin);
+
+			assert_ok!(PolkaSwap::set_relayer_threshold(Origin::root(), 3));
+			assert_err!(PolkaSwap::remove_relayer(Origin::root(), relayer(3)), Error::<Test>::InvalidThreshold);
+
+			assert_ok!(PolkaSwap::set_relayer_threshold(Origin::root(), 2));
+			assert_ok!(PolkaSwap::remove_relayer(Origin::root(), relayer(3)));
+			assert_eq!(PolkaSwap::relayers(), vec![relayer(1), relayer(2)]);
+		});
+	}
+
+	#[test]
+	fn start_block_is_mandatory() {
+		new_test_ext().execute_with(|| {
+			assert_err!(PolkaSwap::set_eth_start_block(Origin::root(), 0), Error::<Test>::InvalidStartBlock);
+
+			// Storage of chain which was started without start block
+			EthStartBlock::put(0);
+			assert_eq!(PolkaSwap::next_eth_block(), None);
+			assert_err!(
+				PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), block(START_BLOCK, vec![])),
+				Error::<Test>::EthStartBlockNotSet
+			);
+
+			assert_ok!(PolkaSwap::set_eth_start_block(Origin::root(), START_BLOCK));
+			sync(block(START_BLOCK, vec![]));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+
+			// Start block is kept while synced blocks could be rolled back to it
+			assert_err!(
+				PolkaSwap::set_eth_start_block(Origin::root(), START_BLOCK + 10),
+				Error::<Test>::EthSyncStarted
+			);
+			assert_ok!(PolkaSwap::rollback_eth_sync(Origin::root(), 0));
+			assert_ok!(PolkaSwap::set_eth_start_block(Origin::root(), START_BLOCK + 10));
+			assert_eq!(PolkaSwap::next_eth_block(), Some(START_BLOCK + 10));
+		});
+	}
+
+	#[test]
+	fn votes_are_cleared_when_block_is_applied() {
+		new_test_ext().execute_with(|| {
+			let be = block(START_BLOCK, vec![]);
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(1)), be.clone()));
+			assert_ok!(PolkaSwap::sync_eth_block(Origin::signed(relayer(2)), be));
+
+			assert_eq!(PolkaSwap::relayer_votes(START_BLOCK, relayer(1)), None::<H256>);
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+		});
+	}
+}
+
+mod reorg {
+	use super::*;
+
+	#[test]
+	fn sync_is_halted_when_block_is_not_child_of_last_synced() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![]));
+			sync(fork_block(START_BLOCK + 1, vec![deposit_eth(eth_address(1), ETH)]));
+
+			assert!(PolkaSwap::eth_sync_halted());
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+			assert_eq!(eth_balance(1), Uint256::from(0));
+			assert!(polkaswap_events().contains(
+				&RawEvent::EthReorgDetected(START_BLOCK + 1, block_hash(START_BLOCK), fork_hash(START_BLOCK))
+			));
+
+			assert_err!(
+				PolkaSwap::sync_eth_block(Origin::signed(relayer(3)), block(START_BLOCK + 1, vec![])),
+				Error::<Test>::EthSyncHalted
+			);
+		});
+	}
+
+	#[test]
+	fn rollback_resumes_sync_from_canonical_chain() {
+		new_test_ext().execute_with(|| {
+			// Relayers followed the fork which diverged after the start block
+			sync(block(START_BLOCK, vec![deposit_eth(eth_address(1), ETH)]));
+			sync(BlockEvents { parent_hash: block_hash(START_BLOCK), ..fork_block(START_BLOCK + 1, vec![]) });
+			sync(fork_block(START_BLOCK + 2, vec![]));
+			sync(fork_block(START_BLOCK + 3, vec![]));
+			sync(block(START_BLOCK + 4, vec![]));
+			assert!(PolkaSwap::eth_sync_halted());
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK + 3);
+
+			assert_err!(PolkaSwap::rollback_eth_sync(Origin::signed(relayer(1)), START_BLOCK), DispatchError::BadOrigin);
+			assert_ok!(PolkaSwap::rollback_eth_sync(Origin::root(), START_BLOCK));
+			assert!(!PolkaSwap::eth_sync_halted());
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+			assert_eq!(PolkaSwap::relayer_votes(START_BLOCK + 4, relayer(1)), None::<H256>);
+
+			sync(block(START_BLOCK + 1, vec![deposit_eth(eth_address(1), ETH)]));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK + 1);
+			assert_eq!(eth_balance(1), Uint256::from(2 * ETH));
+		});
+	}
+
+	#[test]
+	fn rollback_over_processed_logs_is_refused() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![]));
+			sync(block(START_BLOCK + 1, vec![deposit_eth(eth_address(1), ETH)]));
+			sync(block(START_BLOCK + 2, vec![]));
+			sync(fork_block(START_BLOCK + 3, vec![]));
+			assert!(PolkaSwap::eth_sync_halted());
+
+			assert_err!(
+				PolkaSwap::rollback_eth_sync(Origin::root(), START_BLOCK),
+				Error::<Test>::RollbackOverProcessedLogs
+			);
+			assert_err!(PolkaSwap::rollback_eth_sync(Origin::root(), 0), Error::<Test>::RollbackOverProcessedLogs);
+			assert!(PolkaSwap::eth_sync_halted());
+
+			assert_ok!(PolkaSwap::rollback_eth_sync(Origin::root(), START_BLOCK + 1));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK + 1);
+		});
+	}
+
+	#[test]
+	fn rollback_is_limited_by_synced_blocks() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![]));
+
+			assert_err!(
+				PolkaSwap::rollback_eth_sync(Origin::root(), START_BLOCK + 1),
+				Error::<Test>::InvalidRollbackBlock
+			);
+			assert_err!(
+				PolkaSwap::rollback_eth_sync(Origin::root(), START_BLOCK - 1),
+				Error::<Test>::InvalidRollbackBlock
+			);
+
+			// Zero block restarts sync from the start block
+			assert_ok!(PolkaSwap::rollback_eth_sync(Origin::root(), 0));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), 0);
+			sync(block(START_BLOCK, vec![]));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+		});
+	}
+}
+
+mod contract {
+	use super::*;
+
+	use crate::entities::{ContractMethod, Price};
+
+	fn token_balance(user: u8, token: EthAddress) -> Uint256 {
+		PolkaSwap::token_balance(eth_address(user), token)
+	}
+
+	/// Pool of 10 ETH and 10000 USDC provided by the first user, swaps go from index 4
+	fn usdc_pool_with(deposit: ContractMethod, swaps: Vec<ContractMethod>) -> BlockEvents {
+		let mut methods = vec![
+			deposit_eth(eth_address(1), 10 * ETH),
+			deposit_token(usdc(), eth_address(1), 10 * INITIAL_RATIO * USDC),
+			add_liquidity(usdc(), eth_address(1), 10 * ETH),
+			deposit,
+		];
+		methods.extend(swaps);
+		block(START_BLOCK, methods)
+	}
+
+	fn swap_failed(index: u32, kind: ContractMethodKind, error: ContractError) -> RawEvent<sp_core::sr25519::Public, u64> {
+		RawEvent::MethodFailed(
+			START_BLOCK, index, log_id(START_BLOCK as u64 * 1000 + index as u64, 0), eth_address(2), kind, error,
+		)
+	}
+
+	#[test]
+	fn swap_eth_for_exact_tokens_spends_at_most_limit() {
+		new_test_ext().execute_with(|| {
+			// 100 USDC cost 0.101314043139519569 ETH with fee
+			let amount_in = 101_314_043_139_519_569;
+			sync(usdc_pool_with(deposit_eth(eth_address(2), ETH), vec![
+				swap_eth_for_exact_tokens(usdc(), eth_address(2), 100 * USDC, amount_in - 1, START_BLOCK),
+				swap_eth_for_exact_tokens(usdc(), eth_address(2), 100 * USDC, amount_in, START_BLOCK - 1),
+				swap_eth_for_exact_tokens(usdc(), eth_address(2), 100 * USDC, amount_in, START_BLOCK),
+			]));
+
+			let events = polkaswap_events();
+			let kind = ContractMethodKind::SwapETHForExactTokens;
+			assert!(events.contains(&swap_failed(4, kind, ContractError::ExcessiveInputAmount)));
+			assert!(events.contains(&swap_failed(5, kind, ContractError::DeadlineExpired)));
+
+			assert_eq!(eth_balance(2), Uint256::from(ETH - amount_in));
+			assert_eq!(token_balance(2, usdc()), Uint256::from(100 * USDC));
+			assert_eq!(PolkaSwap::pool_eth_liquidity(usdc()), Uint256::from(10 * ETH + amount_in));
+			assert_eq!(PolkaSwap::pool_token_liquidity(usdc()), Uint256::from(9900 * USDC));
+		});
+	}
+
+	#[test]
+	fn swap_exact_tokens_for_eth_gets_at_least_limit() {
+		new_test_ext().execute_with(|| {
+			// 100 USDC are sold for 0.098715803439706129 ETH with fee
+			let amount_out = 98_715_803_439_706_129;
+			sync(usdc_pool_with(deposit_token(usdc(), eth_address(2), 100 * USDC), vec![
+				swap_exact_tokens_for_eth(usdc(), eth_address(2), 100 * USDC, amount_out + 1, START_BLOCK),
+				swap_exact_tokens_for_eth(usdc(), eth_address(2), 100 * USDC, amount_out, START_BLOCK - 1),
+				swap_exact_tokens_for_eth(usdc(), eth_address(2), 100 * USDC, amount_out, START_BLOCK),
+			]));
+
+			let events = polkaswap_events();
+			let kind = ContractMethodKind::SwapExactTokensForETH;
+			assert!(events.contains(&swap_failed(4, kind, ContractError::InsufficientOutputAmount)));
+			assert!(events.contains(&swap_failed(5, kind, ContractError::DeadlineExpired)));
+
+			assert_eq!(eth_balance(2), Uint256::from(amount_out));
+			assert_eq!(token_balance(2, usdc()), Uint256::from(0));
+			assert_eq!(PolkaSwap::pool_eth_liquidity(usdc()), Uint256::from(10 * ETH - amount_out));
+			assert_eq!(PolkaSwap::pool_token_liquidity(usdc()), Uint256::from(10100 * USDC));
+		});
+	}
+
+	#[test]
+	fn swap_tokens_for_exact_eth_spends_at_most_limit() {
+		new_test_ext().execute_with(|| {
+			// 0.1 ETH cost 101.314044 USDC with fee
+			let amount_in = 101_314_044;
+			sync(usdc_pool_with(deposit_token(usdc(), eth_address(2), 200 * USDC), vec![
+				swap_tokens_for_exact_eth(usdc(), eth_address(2), ETH / 10, amount_in - 1, START_BLOCK),
+				swap_tokens_for_exact_eth(usdc(), eth_address(2), ETH / 10, amount_in, START_BLOCK - 1),
+				swap_tokens_for_exact_eth(usdc(), eth_address(2), ETH / 10, amount_in, START_BLOCK),
+			]));
+
+			let events = polkaswap_events();
+			let kind = ContractMethodKind::SwapTokensForExactETH;
+			assert!(events.contains(&swap_failed(4, kind, ContractError::ExcessiveInputAmount)));
+			assert!(events.contains(&swap_failed(5, kind, ContractError::DeadlineExpired)));
+
+			assert_eq!(eth_balance(2), Uint256::from(ETH / 10));
+			assert_eq!(token_balance(2, usdc()), Uint256::from(200 * USDC - amount_in));
+			assert_eq!(PolkaSwap::pool_eth_liquidity(usdc()), Uint256::from(10 * ETH - ETH / 10));
+			assert_eq!(PolkaSwap::pool_token_liquidity(usdc()), Uint256::from(10000 * USDC + amount_in));
+		});
+	}
+
+	#[test]
+	fn failed_method_is_reverted_and_next_methods_are_applied() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![
+				deposit_eth(eth_address(1), 10 * ETH),
+				deposit_token(dai(), eth_address(1), 10 * INITIAL_RATIO * DAI),
+				add_liquidity(dai(), eth_address(1), 10 * ETH),
+				deposit_eth(eth_address(2), ETH),
+				// About 906 tokens could be bought for 1 ETH
+				swap_exact_eth_for_tokens(dai(), eth_address(2), ETH, INITIAL_RATIO * DAI, START_BLOCK),
+				swap_exact_eth_for_tokens(dai(), eth_address(2), ETH, 0, START_BLOCK - 1),
+				deposit_eth(eth_address(2), ETH),
+			]));
+
+			let events = polkaswap_events();
+			assert!(events.contains(&RawEvent::MethodFailed(
+				START_BLOCK, 4, log_id(START_BLOCK as u64 * 1000 + 4, 0), eth_address(2),
+				ContractMethodKind::SwapExactETHForTokens, ContractError::InsufficientOutputAmount,
+			)));
+			assert!(events.contains(&RawEvent::MethodFailed(
+				START_BLOCK, 5, log_id(START_BLOCK as u64 * 1000 + 5, 0), eth_address(2),
+				ContractMethodKind::SwapExactETHForTokens, ContractError::DeadlineExpired,
+			)));
+
+			assert_eq!(eth_balance(2), Uint256::from(2 * ETH));
+			assert_eq!(token_balance(2, dai()), Uint256::from(0));
+			assert_eq!(PolkaSwap::pool_eth_liquidity(dai()), Uint256::from(10 * ETH));
+			assert_eq!(PolkaSwap::pool_token_liquidity(dai()), Uint256::from(10 * INITIAL_RATIO * DAI));
+			assert_eq!(PolkaSwap::eth_last_synced_block(), START_BLOCK);
+		});
+	}
+
+	#[test]
+	fn initial_ratio_is_scaled_by_token_decimals() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![
+				deposit_eth(eth_address(1), 2 * ETH),
+				deposit_token(usdc(), eth_address(1), 5000 * USDC),
+				add_liquidity(usdc(), eth_address(1), 2 * ETH),
+			]));
+
+			assert_eq!(PolkaSwap::pool_eth_liquidity(usdc()), Uint256::from(2 * ETH));
+			assert_eq!(PolkaSwap::pool_token_liquidity(usdc()), Uint256::from(2 * INITIAL_RATIO * USDC));
+			assert_eq!(token_balance(1, usdc()), Uint256::from(3000 * USDC));
+			assert_eq!(eth_balance(1), Uint256::from(0));
+		});
+	}
+
+	#[test]
+	fn add_liquidity_follows_pool_price() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![
+				deposit_eth(eth_address(1), 10 * ETH),
+				deposit_token(dai(), eth_address(1), 10 * INITIAL_RATIO * DAI),
+				add_liquidity(dai(), eth_address(1), 10 * ETH),
+				// Buying half of tokens moves price far from the initial ratio
+				deposit_eth(eth_address(3), 20 * ETH),
+				swap_to_token(dai(), eth_address(3), 5 * INITIAL_RATIO * DAI),
+			]));
+
+			let pool_eth = PolkaSwap::pool_eth_liquidity(dai());
+			let pool_token = PolkaSwap::pool_token_liquidity(dai());
+			let total_supply = PolkaSwap::total_supply(dai());
+			assert_eq!(pool_token, Uint256::from(5 * INITIAL_RATIO * DAI));
+			assert!(pool_eth > Uint256::from(20 * ETH));
+
+			let amount_eth = Uint256::from(2 * ETH);
+			let amount_token = Price::from_reserves(pool_token, pool_eth).unwrap().token_amount(amount_eth).unwrap();
+			assert!(amount_token < Uint256::from(INITIAL_RATIO * DAI / 2));
+
+			sync(block(START_BLOCK + 1, vec![
+				deposit_eth(eth_address(2), 2 * ETH),
+				deposit_token(dai(), eth_address(2), 2 * INITIAL_RATIO * DAI),
+				add_liquidity(dai(), eth_address(2), 2 * ETH),
+			]));
+
+			assert_eq!(eth_balance(2), Uint256::from(0));
+			assert_eq!(
+				token_balance(2, dai()),
+				Uint256::from(2 * INITIAL_RATIO * DAI).checked_sub(amount_token).unwrap()
+			);
+			assert_eq!(PolkaSwap::pool_eth_liquidity(dai()), pool_eth.checked_add(amount_eth).unwrap());
+			assert_eq!(PolkaSwap::pool_token_liquidity(dai()), pool_token.checked_add(amount_token).unwrap());
+
+			// Liquidity is proportional to the share of the pool, token amount is rounded down
+			let liquidity = PolkaSwap::liquidity_balance(eth_address(2), dai());
+			let by_eth = amount_eth.checked_mul(total_supply).unwrap().checked_div(pool_eth).unwrap();
+			assert!(liquidity <= by_eth && by_eth.checked_sub(liquidity).unwrap() <= Uint256::from(1));
+		});
+	}
+}
+
+mod processed_logs {
+	use super::*;
+
+	#[test]
+	fn processed_logs_are_skipped() {
+		new_test_ext().execute_with(|| {
+			let deposit = (log_id(1, 0), deposit_eth(eth_address(1), ETH));
+			sync(BlockEvents { methods: vec![deposit, deposit], ..block(START_BLOCK, vec![]) });
+			assert_eq!(eth_balance(1), Uint256::from(ETH));
+			assert!(PolkaSwap::is_log_processed(&log_id(1, 0)));
+
+			// The same log in another block, e.g. re-mined after reorg
+			sync(BlockEvents {
+				methods: vec![deposit, (log_id(1, 1), deposit_eth(eth_address(1), ETH))],
+				..block(START_BLOCK + 1, vec![])
+			});
+			assert_eq!(eth_balance(1), Uint256::from(2 * ETH));
+			assert_eq!(PolkaSwap::block_processed_logs(START_BLOCK), vec![log_id(1, 0)]);
+			assert_eq!(PolkaSwap::block_processed_logs(START_BLOCK + 1), vec![log_id(1, 1)]);
+		});
+	}
+
+	#[test]
+	fn processed_logs_are_pruned_by_depth() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(PolkaSwap::set_processed_logs_depth(Origin::root(), 2));
+			for n in START_BLOCK..=START_BLOCK + 3 {
+				sync(block(n, vec![deposit_eth(eth_address(1), ETH)]));
+			}
+
+			let first_log = log_id(START_BLOCK as u64 * 1000, 0);
+			assert!(!PolkaSwap::is_log_processed(&first_log));
+			assert!(PolkaSwap::block_processed_logs(START_BLOCK + 1).is_empty());
+			assert_eq!(PolkaSwap::block_processed_logs(START_BLOCK + 2).len(), 1);
+			assert_eq!(PolkaSwap::processed_logs_pruned_block(), START_BLOCK + 1);
+
+			// Pruned log is applied again, so depth should exceed reorg depth
+			sync(BlockEvents {
+				methods: vec![(first_log, deposit_eth(eth_address(1), ETH))],
+				..block(START_BLOCK + 4, vec![])
+			});
+			assert_eq!(eth_balance(1), Uint256::from(5 * ETH));
+		});
+	}
+
+	#[test]
+	fn pruning_catches_up_gradually() {
+		new_test_ext().execute_with(|| {
+			let last_block = START_BLOCK + 3 * MAX_ETH_SYNC_BATCH_SIZE;
+			for n in START_BLOCK..=last_block {
+				PolkaSwap::mark_log_processed(log_id(n as u64, 0), n);
+			}
+
+			// Logs are kept forever with zero depth
+			PolkaSwap::prune_processed_logs(last_block);
+			assert!(PolkaSwap::is_log_processed(&log_id(START_BLOCK as u64, 0)));
+
+			assert_ok!(PolkaSwap::set_processed_logs_depth(Origin::root(), 10));
+			PolkaSwap::prune_processed_logs(last_block);
+			let pruned_block = START_BLOCK + MAX_ETH_SYNC_BATCH_SIZE;
+			assert_eq!(PolkaSwap::processed_logs_pruned_block(), pruned_block);
+			assert!(!PolkaSwap::is_log_processed(&log_id(START_BLOCK as u64, 0)));
+			assert!(!PolkaSwap::is_log_processed(&log_id(pruned_block as u64, 0)));
+			assert!(PolkaSwap::is_log_processed(&log_id(pruned_block as u64 + 1, 0)));
+
+			PolkaSwap::prune_processed_logs(last_block + 1);
+			assert_eq!(PolkaSwap::processed_logs_pruned_block(), pruned_block + MAX_ETH_SYNC_BATCH_SIZE + 1);
+
+			// Pruning stops at depth
+			PolkaSwap::prune_processed_logs(last_block + 2);
+			PolkaSwap::prune_processed_logs(last_block + 3);
+			assert_eq!(PolkaSwap::processed_logs_pruned_block(), last_block + 3 - 10);
+			assert!(PolkaSwap::is_log_processed(&log_id(last_block as u64 - 6, 0)));
+		});
+	}
+}
+
+mod simulate {
+	use super::*;
+
+	#[test]
+	fn simulate_leaves_storage_untouched() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![
+				deposit_eth(eth_address(1), 10 * ETH),
+				deposit_token(dai(), eth_address(1), 10 * INITIAL_RATIO * DAI),
+				add_liquidity(dai(), eth_address(1), 10 * ETH),
+			]));
+			let storage_root = sp_io::storage::root();
+			let events = System::events().len();
+
+			// Methods are applied one after another, deadline is checked against the next block
+			let results = PolkaSwap::simulate(vec![
+				deposit_eth(eth_address(2), ETH),
+				swap_exact_eth_for_tokens(dai(), eth_address(2), ETH, 0, START_BLOCK + 1),
+				swap_exact_eth_for_tokens(dai(), eth_address(2), ETH, 0, START_BLOCK),
+			]);
+			assert!(results[0].is_ok());
+			assert!(results[1].is_ok());
+			assert_eq!(results[2], Err(ContractError::DeadlineExpired));
+
+			assert_eq!(sp_io::storage::root(), storage_root);
+			assert_eq!(System::events().len(), events);
+			assert_eq!(eth_balance(2), Uint256::from(0));
+			assert_eq!(PolkaSwap::pool_eth_liquidity(dai()), Uint256::from(10 * ETH));
+		});
+	}
+}
+
+mod tokens {
+	use super::*;
+
+	#[test]
+	fn register_token_creates_pool() {
+		new_test_ext().execute_with(|| {
+			let token = eth_address(0x77);
+			assert_err!(PolkaSwap::register_token(Origin::signed(relayer(1)), token, 8), DispatchError::BadOrigin);
+			assert_eq!(PolkaSwap::get_pool(&token), None);
+
+			assert_ok!(PolkaSwap::register_token(Origin::root(), token, 8));
+			assert_eq!(PolkaSwap::token_decimals(token), Some(8));
+			assert!(PolkaSwap::get_pool(&token).is_some());
+			assert!(polkaswap_events().contains(&RawEvent::TokenRegistered(token, 8)));
+
+			// Decimals of registered token are updated
+			assert_ok!(PolkaSwap::register_token(Origin::root(), token, 6));
+			assert_eq!(PolkaSwap::token_decimals(token), Some(6));
+		});
+	}
+
+	#[test]
+	fn pool_and_balances_follow_new_token_address() {
+		new_test_ext().execute_with(|| {
+			sync(block(START_BLOCK, vec![
+				deposit_eth(eth_address(1), 10 * ETH),
+				deposit_token(dai(), eth_address(1), 20 * INITIAL_RATIO * DAI),
+				add_liquidity(dai(), eth_address(1), 10 * ETH),
+				deposit_token(dai(), eth_address(2), DAI),
+				deposit_token(usdc(), eth_address(2), USDC),
+			]));
+			let pool = PolkaSwap::get_pool(&dai()).unwrap();
+			let liquidity = PolkaSwap::liquidity_balance(eth_address(1), dai());
+			let new_dai = eth_address(0xdb);
+
+			// Balances of all tokens are counted: 3 token balances and 1 liquidity balance
+			assert_err!(
+				PolkaSwap::set_token_contract_address(Origin::root(), dai(), new_dai, 3),
+				Error::<Test>::TooManyBalances
+			);
+			assert_eq!(PolkaSwap::token_decimals(dai()), Some(18));
+			assert_err!(
+				PolkaSwap::set_token_contract_address(Origin::root(), new_dai, eth_address(0xdc), 4),
+				Error::<Test>::TokenNotRegistered
+			);
+			assert_err!(
+				PolkaSwap::set_token_contract_address(Origin::root(), dai(), usdc(), 4),
+				Error::<Test>::TokenAlreadyRegistered
+			);
+
+			assert_ok!(PolkaSwap::set_token_contract_address(Origin::root(), dai(), new_dai, 4));
+			assert!(polkaswap_events().contains(&RawEvent::TokenContractAddressUpdated(dai(), new_dai)));
+
+			assert_eq!(PolkaSwap::token_decimals(new_dai), Some(18));
+			assert_eq!(PolkaSwap::get_pool(&new_dai).map(|p| (p.token_reserve, p.eth_reserve, p.total_supply)),
+					   Some((pool.token_reserve, pool.eth_reserve, pool.total_supply)));
+			assert_eq!(PolkaSwap::token_balance(eth_address(1), new_dai), Uint256::from(10 * INITIAL_RATIO * DAI));
+			assert_eq!(PolkaSwap::token_balance(eth_address(2), new_dai), Uint256::from(DAI));
+			assert_eq!(PolkaSwap::liquidity_balance(eth_address(1), new_dai), liquidity);
+
+			// Old token address is cleared, other pools are not changed
+			assert_eq!(PolkaSwap::token_decimals(dai()), None);
+			assert_eq!(PolkaSwap::get_pool(&dai()), None);
+			assert_eq!(PolkaSwap::pool_eth_liquidity(dai()), Uint256::from(0));
+			assert_eq!(PolkaSwap::total_supply(dai()), Uint256::from(0));
+			assert_eq!(PolkaSwap::token_balance(eth_address(1), dai()), Uint256::from(0));
+			assert_eq!(PolkaSwap::liquidity_balance(eth_address(1), dai()), Uint256::from(0));
+			assert_eq!(PolkaSwap::token_balance(eth_address(2), usdc()), Uint256::from(USDC));
+		});
+	}
+}
+
+mod withdrawals {
+	use super::*;
+
+	use frame_support::traits::OnFinalize;
+	use sp_core::ecdsa;
+	use crate::entities::{EthAddress, Withdrawal};
+	use crate::eth_bridge::merkle::verify_proof;
+	use crate::eth_signer::eth_address_from_raw;
+
+	/// Validator key which signs withdrawals
+	fn eth_signer(n: u8) -> (secp256k1::SecretKey, EthAddress) {
+		let secret = secp256k1::SecretKey::parse(&[n; 32]).unwrap();
+		let public = secp256k1::PublicKey::from_secret_key(&secret).serialize();
+		(secret, eth_address_from_raw(&public[1..]))
+	}
+
+	/// Signature as the node signs withdrawals: v is recovery id 0 | 1
+	fn sign_withdrawal(secret: &secp256k1::SecretKey, nonce: u64) -> ecdsa::Signature {
+		let digest = PolkaSwap::withdrawals(nonce).unwrap().eth_signed_message_hash();
+		let (signature, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(digest.as_fixed_bytes()), secret);
+
+		let mut raw = [0u8; 65];
+		raw[..64].copy_from_slice(&signature.serialize());
+		raw[64] = recovery_id.serialize();
+		ecdsa::Signature::from_raw(raw)
+	}
+
+	fn withdraw_block(number: u32) -> BlockEvents {
+		block(number, vec![
+			deposit_eth(eth_address(1), 2 * ETH),
+			deposit_token(dai(), eth_address(1), DAI),
+			withdraw_eth(eth_address(1), ETH),
+			withdraw_token(dai(), eth_address(1), DAI),
+			// User without balance has nothing to withdraw
+			withdraw_eth(eth_address(2), ETH),
+		])
+	}
+
+	#[test]
+	fn withdrawals_are_queued_with_increasing_nonces() {
+		new_test_ext().execute_with(|| {
+			sync(withdraw_block(START_BLOCK));
+
+			assert_eq!(PolkaSwap::withdrawal_nonce(), 2);
+			assert_eq!(PolkaSwap::pending_withdrawals(), vec![0, 1]);
+			assert_eq!(PolkaSwap::withdrawals(0), Some(Withdrawal {
+				nonce: 0,
+				recipient: eth_address(1),
+				asset: EthAddress::default(),
+				amount: Uint256::from(ETH),
+				vault: vault(),
+			}));
+			assert_eq!(PolkaSwap::withdrawals(1), Some(Withdrawal {
+				nonce: 1,
+				recipient: eth_address(1),
+				asset: dai(),
+				amount: Uint256::from(DAI),
+				vault: vault(),
+			}));
+			assert_eq!(eth_balance(1), Uint256::from(ETH));
+			assert_eq!(PolkaSwap::token_balance(eth_address(1), dai()), Uint256::from(0));
+
+			// Proof is available when block is finalized
+			assert_eq!(PolkaSwap::get_withdrawal_proof(0), None);
+		});
+	}
+
+	#[test]
+	fn withdrawals_are_sealed_at_block_finalization() {
+		new_test_ext().execute_with(|| {
+			sync(withdraw_block(START_BLOCK));
+			PolkaSwap::on_finalize(1);
+
+			assert!(PolkaSwap::pending_withdrawals().is_empty());
+			assert_eq!(PolkaSwap::block_withdrawals(1), vec![0, 1]);
+			assert_eq!(PolkaSwap::withdrawal_block(1), Some(1));
+			let root = PolkaSwap::withdrawal_root(1).unwrap();
+			assert!(polkaswap_events().contains(&RawEvent::WithdrawalsQueued(1, root, vec![0, 1])));
+
+			for nonce in 0..2 {
+				let proof = PolkaSwap::get_withdrawal_proof(nonce).unwrap();
+				assert_eq!(proof.block_number, 1);
+				assert_eq!(proof.root, root);
+				assert!(verify_proof(&root, &proof.withdrawal.leaf_hash(), &proof.proof));
+			}
+
+			// Withdrawals of the next block are sealed in another tree
+			System::set_block_number(2);
+			sync(withdraw_block(START_BLOCK + 1));
+			PolkaSwap::on_finalize(2);
+			let proof = PolkaSwap::get_withdrawal_proof(3).unwrap();
+			assert_eq!(proof.block_number, 2);
+			assert_ne!(proof.root, root);
+			assert!(verify_proof(&proof.root, &proof.withdrawal.leaf_hash(), &proof.proof));
+
+			// Blocks without withdrawals have no root
+			PolkaSwap::on_finalize(3);
+			assert_eq!(PolkaSwap::withdrawal_root(3), None);
+		});
+	}
+
+	#[test]
+	fn signatures_of_eth_signers_are_aggregated() {
+		new_test_ext().execute_with(|| {
+			let (secret_1, signer_1) = eth_signer(1);
+			let (secret_2, signer_2) = eth_signer(2);
+			let (secret_3, _) = eth_signer(3);
+			assert_ok!(PolkaSwap::set_eth_signers(Origin::root(), vec![signer_1, signer_2]));
+			sync(withdraw_block(START_BLOCK));
+			PolkaSwap::on_finalize(1);
+
+			let signature_1 = sign_withdrawal(&secret_1, 0);
+			assert_ok!(PolkaSwap::submit_withdrawal_signatures(Origin::signed(relayer(1)), vec![(0, signature_1.clone())]));
+			assert!(polkaswap_events().contains(&RawEvent::WithdrawalSigned(0, signer_1)));
+
+			// Signature of the same signer is stored once
+			assert_ok!(PolkaSwap::submit_withdrawal_signatures(
+				Origin::signed(relayer(2)),
+				vec![(0, signature_1.clone()), (0, sign_withdrawal(&secret_2, 0))]
+			));
+			assert_eq!(
+				PolkaSwap::withdrawal_signatures(0).into_iter().map(|(signer, _)| signer).collect::<Vec<_>>(),
+				vec![signer_1, signer_2]
+			);
+			assert_eq!(polkaswap_events().iter().filter(|e| **e == RawEvent::WithdrawalSigned(0, signer_1)).count(), 1);
+
+			// Batch with signature of unknown signer is rejected as a whole
+			assert_err!(
+				PolkaSwap::submit_withdrawal_signatures(
+					Origin::signed(relayer(1)),
+					vec![(1, sign_withdrawal(&secret_1, 1)), (1, sign_withdrawal(&secret_3, 1))]
+				),
+				Error::<Test>::UnknownEthSigner
+			);
+			assert!(PolkaSwap::withdrawal_signatures(1).is_empty());
+			assert_err!(
+				PolkaSwap::submit_withdrawal_signatures(Origin::signed(relayer(1)), vec![(5, signature_1)]),
+				Error::<Test>::UnknownWithdrawal
+			);
+
+			let bundle = PolkaSwap::get_withdrawal_bundle(0).unwrap();
+			assert_eq!(bundle.vault, vault());
+			assert_eq!(bundle.signers, vec![signer_1, signer_2]);
+			for signature in bundle.signatures.iter() {
+				assert_eq!(signature.len(), 65);
+				assert!(signature[64] == 27 || signature[64] == 28);
+			}
+		});
+	}
+
+	#[test]
+	fn withdrawals_are_signed_for_vault_they_were_queued_for() {
+		new_test_ext().execute_with(|| {
+			let (secret, signer) = eth_signer(1);
+			assert_ok!(PolkaSwap::set_eth_signers(Origin::root(), vec![signer]));
+			sync(withdraw_block(START_BLOCK));
+			PolkaSwap::on_finalize(1);
+			let signature = sign_withdrawal(&secret, 0);
+
+			let new_vault = eth_address(0xab);
+			assert_ok!(PolkaSwap::set_vault_contract_address(Origin::root(), new_vault));
+			assert_ok!(PolkaSwap::submit_withdrawal_signatures(Origin::signed(relayer(1)), vec![(0, signature)]));
+			assert_eq!(PolkaSwap::get_withdrawal_bundle(0).map(|b| (b.vault, b.signers)), Some((vault(), vec![signer])));
+
+			// New withdrawals are paid by the new vault
+			System::set_block_number(2);
+			sync(withdraw_block(START_BLOCK + 1));
+			assert_eq!(PolkaSwap::withdrawals(2).map(|w| w.vault), Some(new_vault));
+		});
+	}
+}
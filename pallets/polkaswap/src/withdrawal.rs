@@ -0,0 +1,109 @@
+use frame_support::{debug, StorageMap, StorageValue};
+use sp_core::ecdsa;
+use sp_std::prelude::*;
+
+use crate::{BlockWithdrawals, Error, Module, PendingWithdrawals, RawEvent, Trait, VaultContractAddress,
+			WithdrawalBlock, WithdrawalNonce, WithdrawalRoots, WithdrawalSignatures, Withdrawals};
+use crate::entities::{EthAddress, Uint256, Withdrawal, WithdrawalBundle, WithdrawalProof};
+use crate::eth_bridge::merkle::{merkle_proof, merkle_root};
+use crate::eth_signer::eth_address_from_raw;
+
+/// Adds withdrawal to the queue, it would be sealed in merkle tree at the end of the block
+/// Withdrawal keeps current vault, so it's signed for the same vault after vault address is updated
+/// @return nonce of withdrawal
+pub(crate) fn queue_withdrawal(recipient: EthAddress, asset: EthAddress, amount: Uint256) -> u64 {
+	let nonce = WithdrawalNonce::get();
+	WithdrawalNonce::put(nonce + 1);
+
+	let withdrawal = Withdrawal { nonce, recipient, asset, amount, vault: VaultContractAddress::get() };
+	debug::info!("queue withdrawal: {:?}", withdrawal);
+
+	Withdrawals::insert(nonce, withdrawal);
+	PendingWithdrawals::append(nonce);
+	nonce
+}
+
+impl<T: Trait> Module<T> {
+	/// Builds merkle tree for withdrawals queued in the block and stores its root
+	pub(crate) fn seal_withdrawals(block_number: T::BlockNumber) {
+		let nonces = PendingWithdrawals::take();
+		if nonces.is_empty() {
+			return;
+		}
+
+		let root = merkle_root(&Self::withdrawal_leaves(&nonces));
+
+		for nonce in nonces.iter() {
+			WithdrawalBlock::<T>::insert(nonce, block_number);
+		}
+		WithdrawalRoots::<T>::insert(block_number, root);
+		BlockWithdrawals::<T>::insert(block_number, nonces.clone());
+
+		Self::deposit_event(RawEvent::WithdrawalsQueued(block_number, root, nonces));
+	}
+
+	/// Withdrawals with nonces from `from_nonce`, at most `limit` items
+	pub fn get_withdrawals(from_nonce: u64, limit: u32) -> Vec<Withdrawal> {
+		let to_nonce = sp_std::cmp::min(from_nonce.saturating_add(limit as u64), WithdrawalNonce::get());
+		(from_nonce..to_nonce)
+			.filter_map(|nonce| Withdrawals::get(nonce))
+			.collect()
+	}
+
+	/// Merkle proof for withdrawal, it's available when block with withdrawal is finalized
+	pub fn get_withdrawal_proof(nonce: u64) -> Option<WithdrawalProof<T::BlockNumber>> {
+		let withdrawal = Withdrawals::get(nonce)?;
+		let block_number = WithdrawalBlock::<T>::get(nonce)?;
+		let root = WithdrawalRoots::<T>::get(block_number)?;
+
+		let nonces = BlockWithdrawals::<T>::get(block_number);
+		let index = nonces.iter().position(|n| *n == nonce)?;
+		let proof = merkle_proof(&Self::withdrawal_leaves(&nonces), index);
+
+		Some(WithdrawalProof { withdrawal, block_number, root, proof })
+	}
+
+	/// Withdrawal with validators signatures which could be submitted to vault contract
+	pub fn get_withdrawal_bundle(nonce: u64) -> Option<WithdrawalBundle> {
+		let withdrawal = Withdrawals::get(nonce)?;
+		let (signers, signatures) = WithdrawalSignatures::get(nonce)
+			.into_iter()
+			.map(|(signer, signature)| {
+				let mut raw: Vec<u8> = signature.as_ref().to_vec();
+				// Solidity ecrecover expects v = 27 | 28
+				if raw[64] < 27 {
+					raw[64] += 27;
+				}
+				(signer, raw)
+			})
+			.unzip();
+
+		let vault = withdrawal.vault;
+		Some(WithdrawalBundle { withdrawal, vault, signers, signatures })
+	}
+
+	/// Recovers ethereum address which signed withdrawal and checks that it's validator address
+	pub(crate) fn recover_withdrawal_signer(nonce: u64, signature: &ecdsa::Signature) -> Result<EthAddress, Error<T>> {
+		let withdrawal = Withdrawals::get(nonce).ok_or(<Error<T>>::UnknownWithdrawal)?;
+		let digest = withdrawal.eth_signed_message_hash();
+
+		let mut raw_signature = [0u8; 65];
+		raw_signature.copy_from_slice(signature.as_ref());
+
+		let public = sp_io::crypto::secp256k1_ecdsa_recover(&raw_signature, digest.as_fixed_bytes())
+			.map_err(|_| <Error<T>>::InvalidWithdrawalSignature)?;
+
+		let signer = eth_address_from_raw(&public);
+		if !Self::eth_signers().contains(&signer) {
+			return Err(<Error<T>>::UnknownEthSigner);
+		}
+		Ok(signer)
+	}
+
+	fn withdrawal_leaves(nonces: &[u64]) -> Vec<sp_core::H256> {
+		nonces.iter()
+			.filter_map(|nonce| Withdrawals::get(nonce))
+			.map(|w| w.leaf_hash())
+			.collect()
+	}
+}
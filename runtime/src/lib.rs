@@ -18,6 +18,7 @@ use pallet_grandpa::fg_primitives;
 
 /// Import the polkaswap pallet.
 pub use pallet_polkaswap;
+use pallet_polkaswap::entities::{EthAddress, Uint256};
 
 pub use pallet_timestamp::Call as TimestampCall;
 use sp_api::impl_runtime_apis;
@@ -273,7 +274,11 @@ impl pallet_sudo::Trait for Runtime {
 //
 
 parameter_types! {
-	pub const EthProviderEndpoint : &'static str = env!("WEB3PROVIDER");
+	/// Fallback endpoint, node sets endpoints at start with `--eth-rpc-url`
+	pub const EthProviderEndpoint : &'static str = match option_env!("WEB3PROVIDER") {
+		Some(url) if !url.is_empty() => url,
+		_ => "http://localhost:8545",
+	};
 }
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 
@@ -361,7 +366,7 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 
 		// Include the custom logic from the template pallet in the runtime.
-		PolkaSwap: pallet_polkaswap::{Module, Call, Storage, Event<T>},
+		PolkaSwap: pallet_polkaswap::{Module, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -529,6 +534,65 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_polkaswap::runtime_api::WithdrawalApi<Block, BlockNumber> for Runtime {
+		fn withdrawals(from_nonce: u64, limit: u32) -> Vec<pallet_polkaswap::entities::Withdrawal> {
+			PolkaSwap::get_withdrawals(from_nonce, limit)
+		}
+
+		fn withdrawal_proof(
+			nonce: u64,
+		) -> Option<pallet_polkaswap::entities::WithdrawalProof<BlockNumber>> {
+			PolkaSwap::get_withdrawal_proof(nonce)
+		}
+
+		fn withdrawal_bundle(nonce: u64) -> Option<pallet_polkaswap::entities::WithdrawalBundle> {
+			PolkaSwap::get_withdrawal_bundle(nonce)
+		}
+	}
+
+	impl pallet_polkaswap::runtime_api::PolkaSwapApi<Block> for Runtime {
+		fn balances(user: EthAddress) -> pallet_polkaswap::entities::UserBalances {
+			PolkaSwap::get_balances(&user)
+		}
+
+		fn pool(token: EthAddress) -> Option<pallet_polkaswap::entities::Pool> {
+			PolkaSwap::get_pool(&token)
+		}
+
+		fn quote_exact_input(
+			token: EthAddress,
+			direction: pallet_polkaswap::entities::SwapDirection,
+			amount_in: Uint256,
+		) -> Result<Uint256, pallet_polkaswap::ContractError> {
+			PolkaSwap::quote_exact_input(&token, direction, amount_in)
+		}
+
+		fn quote_exact_output(
+			token: EthAddress,
+			direction: pallet_polkaswap::entities::SwapDirection,
+			amount_out: Uint256,
+		) -> Result<Uint256, pallet_polkaswap::ContractError> {
+			PolkaSwap::quote_exact_output(&token, direction, amount_out)
+		}
+
+		fn liquidity_position(
+			user: EthAddress,
+			token: EthAddress,
+		) -> Option<pallet_polkaswap::entities::LiquidityPosition> {
+			PolkaSwap::get_liquidity_position(&user, &token)
+		}
+
+		fn sync_status() -> pallet_polkaswap::entities::SyncStatus {
+			PolkaSwap::get_sync_status()
+		}
+
+		fn simulate(
+			methods: Vec<pallet_polkaswap::entities::ContractMethod>,
+		) -> Vec<Result<pallet_polkaswap::ContractEvent, pallet_polkaswap::ContractError>> {
+			PolkaSwap::simulate(methods)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(